@@ -24,24 +24,13 @@ const conn = new Connection(RPC_URL, COMMITMENT);
 const app = express();
 
 /* ----------- State layout decoding ----------- */
+// Zero-copy `State` header followed by `asset_count` packed `AssetEntry` rows
+// (see programs/oracle/src/state/oracle_state.rs)
 const DISC_LEN = 8;
-const TRIP = { price1: 0, price2: 8, price3: 16, price4: 24, ts1: 32, ts2: 40, ts3: 48, ts4: 56, SIZE: 64 };
-const OFF = {
-  update_authority: 0,
-  btc: 32,
-  eth: 32 + TRIP.SIZE,
-  sol: 32 + TRIP.SIZE * 2,
-  hype: 32 + TRIP.SIZE * 3,
-  zec: 32 + TRIP.SIZE * 4,
-  tsla: 32 + TRIP.SIZE * 5,
-  nvda: 32 + TRIP.SIZE * 6,
-  mstr: 32 + TRIP.SIZE * 7,
-  gold: 32 + TRIP.SIZE * 8,
-  silver: 32 + TRIP.SIZE * 9,
-  decimals: 32 + TRIP.SIZE * 10,
-  bump: 32 + TRIP.SIZE * 10 + 1,
-};
-const PAYLOAD_MIN = 32 + TRIP.SIZE * 10 + 2;
+const HEADER = { update_authority: 0, asset_count: 200, paused: 254, SIZE: 256 };
+const HEADER_END = DISC_LEN + HEADER.SIZE; // State::HEADER_END
+const ENTRY = { id: 0, symbol: 1, decimals: 17, status: 19, prices: 56, SIZE: 352 };
+const TRIP = { price1: 0, price2: 8, price3: 16, price4: 24, ts1: 32, ts2: 40, ts3: 48, ts4: 56 };
 
 function readI64LE(b, o) {
   const buf = Buffer.isBuffer(b) ? b : Buffer.from(b);
@@ -52,6 +41,10 @@ function readU8(b, o) {
   const buf = Buffer.isBuffer(b) ? b : Buffer.from(b);
   return buf.readUInt8(o);
 }
+function readU32(b, o) {
+  const buf = Buffer.isBuffer(b) ? b : Buffer.from(b);
+  return buf.readUInt32LE(o);
+}
 function decodeTrip(buf, base) {
   return {
     p1: readI64LE(buf, base + TRIP.price1),
@@ -87,28 +80,32 @@ async function readOracleState() {
   }
 
   const data = Buffer.isBuffer(info.data) ? info.data : Buffer.from(info.data ?? []);
-  if (!data || data.length < DISC_LEN + PAYLOAD_MIN) {
+  const assetCount = data.length >= HEADER_END ? readU32(data, DISC_LEN + HEADER.asset_count) : 0;
+  const expected = HEADER_END + assetCount * ENTRY.SIZE;
+  if (data.length < HEADER_END || data.length < expected) {
     return {
       ctxSlot,
       pda: pda.toBase58(),
       exists: false,
-      message: `State too small: ${data?.length ?? 0} < ${DISC_LEN + PAYLOAD_MIN}`,
+      message: `State too small: ${data?.length ?? 0} < ${Math.max(HEADER_END, expected)}`,
     };
   }
 
-  const payload = data.subarray(DISC_LEN);
-  // HARDCODED: Using 8 decimals for test program (on-chain state has 6)
-  const decimals = 8;
-  const btc = decodeTrip(payload, OFF.btc);
-  const eth = decodeTrip(payload, OFF.eth);
-  const sol = decodeTrip(payload, OFF.sol);
-  const hype = decodeTrip(payload, OFF.hype);
-  const zec = decodeTrip(payload, OFF.zec);
-  const tsla = decodeTrip(payload, OFF.tsla);
-  const nvda = decodeTrip(payload, OFF.nvda);
-  const mstr = decodeTrip(payload, OFF.mstr);
-  const gold = decodeTrip(payload, OFF.gold);
-  const silver = decodeTrip(payload, OFF.silver);
+  const entries = [];
+  for (let i = 0; i < assetCount; i++) {
+    const base = HEADER_END + i * ENTRY.SIZE;
+    const symbol = data
+      .subarray(base + ENTRY.symbol, base + ENTRY.symbol + 16)
+      .toString("utf8")
+      .replace(/\0+$/, "");
+    entries.push({
+      symbol,
+      decimals: readU8(data, base + ENTRY.decimals),
+      trip: decodeTrip(data, base + ENTRY.prices),
+    });
+  }
+  // Prices carry per-asset decimals; the header shows the first asset's
+  const decimals = entries[0]?.decimals ?? null;
 
   const now = Date.now();
   const validMs = (x) => Number.isFinite(Number(x)) && Number(x) > 1e11 && Number(x) < 8.64e15;
@@ -119,25 +116,14 @@ async function readOracleState() {
   };
   const ageOf = (ms) => (validMs(ms) ? now - Number(ms) : null);
 
-  const mkRows = (t) => ([
+  const mkRows = (t, decimals) => ([
     { price: toHuman2(t.p1, decimals), ts: safeIso(t.t1), age: ageOf(t.t1) },
     { price: toHuman2(t.p2, decimals), ts: safeIso(t.t2), age: ageOf(t.t2) },
     { price: toHuman2(t.p3, decimals), ts: safeIso(t.t3), age: ageOf(t.t3) },
     { price: toHuman2(t.p4, decimals), ts: safeIso(t.t4), age: ageOf(t.t4) },
   ]);
 
-  const groups = {
-    BTC: mkRows(btc),
-    ETH: mkRows(eth),
-    SOL: mkRows(sol),
-    HYPE: mkRows(hype),
-    ZEC: mkRows(zec),
-    TSLA: mkRows(tsla),
-    NVDA: mkRows(nvda),
-    MSTR: mkRows(mstr),
-    GOLD: mkRows(gold),
-    SILVER: mkRows(silver)
-  };
+  const groups = Object.fromEntries(entries.map((e) => [e.symbol, mkRows(e.trip, e.decimals)]));
 
   // Aggregates from current rows (ignore nulls, exclude stale and outliers)
   const agg = {};
@@ -145,9 +131,7 @@ async function readOracleState() {
   const STALE_THRESHOLD_MS = 15000; // 15 seconds
   const OUTLIER_THRESHOLD = 0.10; // 10%
 
-  for (const sym of ["BTC", "ETH", "SOL", "HYPE", "ZEC", "TSLA", "NVDA", "MSTR", "GOLD", "SILVER"]) {
-    const rows = groups[sym];
-
+  for (const [sym, rows] of Object.entries(groups)) {
     // Filter out zero/null prices and stale data (older than 15s)
     const validRows = rows
       .map((r, idx) => ({ ...r, idx, priceNum: Number(r.price) }))
@@ -28,6 +28,7 @@ import {
   LOG_THROTTLE_MS,
   DEFAULT_RPC_URL,
   PROGRAM_ID,
+  onchainAssetId,
 } from '../config/constants';
 
 /**
@@ -445,28 +446,15 @@ export class OracleService extends EventEmitter {
 
     const t0 = Date.now();
 
-    // Collect prices for all 10 assets
-    const btcData = fresh.find((f) => f.sym === 'BTC');
-    const ethData = fresh.find((f) => f.sym === 'ETH');
-    const solData = fresh.find((f) => f.sym === 'SOL');
-    const hypeData = fresh.find((f) => f.sym === 'HYPE');
-    const zecData = fresh.find((f) => f.sym === 'ZEC');
-    const tslaData = fresh.find((f) => f.sym === 'TSLA');
-    const nvdaData = fresh.find((f) => f.sym === 'NVDA');
-    const mstrData = fresh.find((f) => f.sym === 'MSTR');
-    const goldData = fresh.find((f) => f.sym === 'GOLD');
-    const silverData = fresh.find((f) => f.sym === 'SILVER');
-
-    const btcPrice = btcData ? btcData.candI64 : this.lastSentI64.BTC || 0;
-    const ethPrice = ethData ? ethData.candI64 : this.lastSentI64.ETH || 0;
-    const solPrice = solData ? solData.candI64 : this.lastSentI64.SOL || 0;
-    const hypePrice = hypeData ? hypeData.candI64 : this.lastSentI64.HYPE || 0;
-    const zecPrice = zecData ? zecData.candI64 : this.lastSentI64.ZEC || 0;
-    const tslaPrice = tslaData ? tslaData.candI64 : this.lastSentI64.TSLA || 0;
-    const nvdaPrice = nvdaData ? nvdaData.candI64 : this.lastSentI64.NVDA || 0;
-    const mstrPrice = mstrData ? mstrData.candI64 : this.lastSentI64.MSTR || 0;
-    const goldPrice = goldData ? goldData.candI64 : this.lastSentI64.GOLD || 0;
-    const silverPrice = silverData ? silverData.candI64 : this.lastSentI64.SILVER || 0;
+    // Only the assets with a fresh price that exist on chain; one unknown id
+    // would revert the whole batch
+    const updates = fresh.flatMap(({ sym, candI64 }) => {
+      const asset = onchainAssetId(sym);
+      return asset === undefined ? [] : [{ asset, priceI64: candI64 }];
+    });
+    if (updates.length === 0) {
+      return;
+    }
 
     const tRecv = Date.now();
 
@@ -474,16 +462,7 @@ export class OracleService extends EventEmitter {
       const sig = await this.txBuilder.sendBatchPriceUpdate(
         this.config.updaterKeypair,
         this.config.updaterIndex,
-        btcPrice,
-        ethPrice,
-        solPrice,
-        hypePrice,
-        zecPrice,
-        tslaPrice,
-        nvdaPrice,
-        mstrPrice,
-        goldPrice,
-        silverPrice,
+        updates,
         clientTsMs
       );
 
@@ -11,6 +11,11 @@ import { Asset, PythFeeds } from '../types';
  */
 export const PROGRAM_ID = new PublicKey('CcgTMiYkgVfz7cAGkD6835BqfycG5N5Y4aPPHYW1EvKx');
 
+/**
+ * Upgradeable BPF loader; owns the ProgramData account checked by `initialize`
+ */
+export const BPF_LOADER_UPGRADEABLE_ID = new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111');
+
 /**
  * PDA seed for state account
  */
@@ -67,6 +72,31 @@ export const ASSETS: Record<string, Asset> = {
   FARTCOIN: Asset.FARTCOIN,
 };
 
+/**
+ * Assets `initialize` registers on chain (ids 1-10). Any other id fails with
+ * `BadAsset` and reverts the whole batch, so relays must leave it out.
+ */
+export const ONCHAIN_ASSETS: ReadonlySet<Asset> = new Set([
+  Asset.BTC,
+  Asset.ETH,
+  Asset.SOL,
+  Asset.HYPE,
+  Asset.ZEC,
+  Asset.TSLA,
+  Asset.NVDA,
+  Asset.MSTR,
+  Asset.GOLD,
+  Asset.SILVER,
+]);
+
+/**
+ * On-chain asset id for a symbol, or undefined when the asset is not on chain
+ */
+export function onchainAssetId(sym: string): Asset | undefined {
+  const asset = ASSETS[sym];
+  return asset !== undefined && ONCHAIN_ASSETS.has(asset) ? asset : undefined;
+}
+
 /**
  * Pyth Hermes feed IDs (canonical)
  * Note: HYPE not available on Pyth - uses composite oracle only
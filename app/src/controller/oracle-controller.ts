@@ -13,7 +13,7 @@ import { EventEmitter } from 'events';
 import { TransactionBuilder } from '../solana/transaction-builder';
 import { PriceValidator } from './price-validator';
 import { RelaySupervisor } from './relay-supervisor';
-import { DECIMALS, TICK_MS } from '../config/constants';
+import { DECIMALS, TICK_MS, onchainAssetId } from '../config/constants';
 import { toFixedI64, formatPrice } from '../utils/formatting';
 import { colors } from '../config/colors';
 import { Logger } from '../utils/logger';
@@ -375,18 +375,17 @@ export class OracleController extends EventEmitter {
       return;
     }
 
+    // Only the updated assets that exist on chain; one unknown id would revert the batch
+    updates = updates.filter(({ asset }) => onchainAssetId(asset) !== undefined);
+    if (updates.length === 0) {
+      return;
+    }
+
     try {
-      // Get current prices (use last sent if not updated)
-      const btcI64 = updates.find(u => u.asset === 'BTC')?.i64 || this.lastSentI64.BTC || 0;
-      const ethI64 = updates.find(u => u.asset === 'ETH')?.i64 || this.lastSentI64.ETH || 0;
-      const solI64 = updates.find(u => u.asset === 'SOL')?.i64 || this.lastSentI64.SOL || 0;
-      const hypeI64 = updates.find(u => u.asset === 'HYPE')?.i64 || this.lastSentI64.HYPE || 0;
-      const zecI64 = updates.find(u => u.asset === 'ZEC')?.i64 || this.lastSentI64.ZEC || 0;
-      const tslaI64 = updates.find(u => u.asset === 'TSLA')?.i64 || this.lastSentI64.TSLA || 0;
-      const nvdaI64 = updates.find(u => u.asset === 'NVDA')?.i64 || this.lastSentI64.NVDA || 0;
-      const mstrI64 = updates.find(u => u.asset === 'MSTR')?.i64 || this.lastSentI64.MSTR || 0;
-      const goldI64 = updates.find(u => u.asset === 'GOLD')?.i64 || this.lastSentI64.GOLD || 0;
-      const silverI64 = updates.find(u => u.asset === 'SILVER')?.i64 || this.lastSentI64.SILVER || 0;
+      const batch = updates.flatMap(({ asset, i64 }) => {
+        const id = onchainAssetId(asset);
+        return id === undefined ? [] : [{ asset: id, priceI64: i64 }];
+      });
 
       const clientTsMs = Date.now();
 
@@ -394,16 +393,7 @@ export class OracleController extends EventEmitter {
       const sig = await this.txBuilder.sendBatchPriceUpdate(
         this.config.updaterKeypair,
         this.config.updaterIndex,
-        btcI64,
-        ethI64,
-        solI64,
-        hypeI64,
-        zecI64,
-        tslaI64,
-        nvdaI64,
-        mstrI64,
-        goldI64,
-        silverI64,
+        batch,
         clientTsMs
       );
 
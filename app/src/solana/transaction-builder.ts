@@ -12,8 +12,9 @@ import {
   sendAndConfirmTransaction,
   ComputeBudgetProgram,
 } from '@solana/web3.js';
-import { Asset, BlockhashCache, TransactionError } from '../types';
+import { Asset, AssetPriceI64, BlockhashCache, TransactionError } from '../types';
 import {
+  BPF_LOADER_UPGRADEABLE_ID,
   PROGRAM_ID,
   STATE_SEED,
   DISCRIMINATORS,
@@ -29,6 +30,15 @@ function encodeU8(n: number): Buffer {
   return b;
 }
 
+/**
+ * Encode u32 (unsigned 32-bit integer, little-endian)
+ */
+function encodeU32(n: number): Buffer {
+  const b = Buffer.alloc(4);
+  b.writeUInt32LE(n);
+  return b;
+}
+
 /**
  * Encode i64 (signed 64-bit integer as two's complement)
  */
@@ -42,6 +52,19 @@ function encodeI64(n: number): Buffer {
   return b;
 }
 
+/**
+ * Encode Option<u64> (0 = None, 1 + u64 = Some)
+ */
+function encodeOptionU64(n?: number | bigint): Buffer {
+  if (n === undefined) {
+    return encodeU8(0);
+  }
+  const b = Buffer.alloc(9);
+  b.writeUInt8(1);
+  b.writeBigUInt64LE(BigInt(n), 1);
+  return b;
+}
+
 /**
  * Convert PublicKey to Buffer
  */
@@ -104,17 +127,29 @@ export class TransactionBuilder {
   }
 
   /**
-   * Build initialize instruction
+   * Build initialize instruction; only the program's upgrade authority may sign it
    */
-  buildInitializeInstruction(updateAuthority: PublicKey): TransactionInstruction {
+  buildInitializeInstruction(
+    updateAuthority: PublicKey,
+    upgradeAuthority: PublicKey,
+    payer: PublicKey = upgradeAuthority
+  ): TransactionInstruction {
     const data = Buffer.concat([
       Buffer.from(DISCRIMINATORS.initialize),
       publicKeyToBuffer(updateAuthority),
     ]);
 
+    const [programData] = PublicKey.findProgramAddressSync(
+      [this.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE_ID
+    );
+
     const keys = [
       { pubkey: this.statePda, isSigner: false, isWritable: true },
-      { pubkey: updateAuthority, isSigner: true, isWritable: true },
+      { pubkey: this.programId, isSigner: false, isWritable: false },
+      { pubkey: programData, isSigner: false, isWritable: false },
+      { pubkey: upgradeAuthority, isSigner: true, isWritable: false },
+      { pubkey: payer, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
     ];
 
@@ -126,14 +161,16 @@ export class TransactionBuilder {
   }
 
   /**
-   * Build set_price instruction
+   * Build set_price instruction. `seq`, if given, must exceed the last
+   * sequence number this updater used.
    */
   buildSetPriceInstruction(
     asset: Asset,
     index: number,
     priceI64: number,
     clientTsMs: number,
-    signer: PublicKey
+    signer: PublicKey,
+    seq?: number | bigint
   ): TransactionInstruction {
     const data = Buffer.concat([
       Buffer.from(DISCRIMINATORS.set_price),
@@ -141,6 +178,7 @@ export class TransactionBuilder {
       encodeU8(index),
       encodeI64(priceI64),
       encodeI64(clientTsMs),
+      encodeOptionU64(seq),
     ]);
 
     const keys = [
@@ -156,37 +194,23 @@ export class TransactionBuilder {
   }
 
   /**
-   * Build batch_set_prices instruction
+   * Build batch_set_prices instruction (Borsh `Vec<PriceUpdate { asset: u8, price: i64 }>`).
+   * `seq`, if given, must exceed the last sequence number this updater used.
    */
   buildBatchSetPricesInstruction(
     index: number,
-    btcPrice: number,
-    ethPrice: number,
-    solPrice: number,
-    hypePrice: number,
-    zecPrice: number,
-    tslaPrice: number,
-    nvdaPrice: number,
-    mstrPrice: number,
-    goldPrice: number,
-    silverPrice: number,
+    updates: AssetPriceI64[],
     clientTsMs: number,
-    signer: PublicKey
+    signer: PublicKey,
+    seq?: number | bigint
   ): TransactionInstruction {
     const data = Buffer.concat([
       Buffer.from(DISCRIMINATORS.batch_set_prices),
       encodeU8(index),
-      encodeI64(btcPrice),
-      encodeI64(ethPrice),
-      encodeI64(solPrice),
-      encodeI64(hypePrice),
-      encodeI64(zecPrice),
-      encodeI64(tslaPrice),
-      encodeI64(nvdaPrice),
-      encodeI64(mstrPrice),
-      encodeI64(goldPrice),
-      encodeI64(silverPrice),
+      encodeU32(updates.length),
+      ...updates.flatMap(({ asset, priceI64 }) => [encodeU8(asset), encodeI64(priceI64)]),
       encodeI64(clientTsMs),
+      encodeOptionU64(seq),
     ]);
 
     const keys = [
@@ -202,7 +226,8 @@ export class TransactionBuilder {
   }
 
   /**
-   * Initialize state account if it doesn't exist
+   * Initialize state account if it doesn't exist; `payer` must be the
+   * program's upgrade authority and becomes the update authority
    */
   async initializeIfNeeded(payer: Keypair): Promise<boolean> {
     const info = await this.connection.getAccountInfo(this.statePda);
@@ -218,7 +243,7 @@ export class TransactionBuilder {
 
     const tx = new Transaction()
       .add(ComputeBudgetProgram.setComputeUnitLimit({ units: COMPUTE_UNIT_LIMIT }))
-      .add(this.buildInitializeInstruction(payer.publicKey));
+      .add(this.buildInitializeInstruction(payer.publicKey, payer.publicKey));
 
     tx.feePayer = payer.publicKey;
     tx.recentBlockhash = this.getBlockhash();
@@ -238,40 +263,16 @@ export class TransactionBuilder {
   async sendBatchPriceUpdate(
     payer: Keypair,
     index: number,
-    btcPrice: number,
-    ethPrice: number,
-    solPrice: number,
-    hypePrice: number,
-    zecPrice: number,
-    tslaPrice: number,
-    nvdaPrice: number,
-    mstrPrice: number,
-    goldPrice: number,
-    silverPrice: number,
-    clientTsMs: number
+    updates: AssetPriceI64[],
+    clientTsMs: number,
+    seq?: number | bigint
   ): Promise<string> {
     // Ensure fresh blockhash
     await this.ensureBlockhashFresh();
 
     const tx = new Transaction()
       .add(ComputeBudgetProgram.setComputeUnitLimit({ units: COMPUTE_UNIT_LIMIT }))
-      .add(
-        this.buildBatchSetPricesInstruction(
-          index,
-          btcPrice,
-          ethPrice,
-          solPrice,
-          hypePrice,
-          zecPrice,
-          tslaPrice,
-          nvdaPrice,
-          mstrPrice,
-          goldPrice,
-          silverPrice,
-          clientTsMs,
-          payer.publicKey
-        )
-      );
+      .add(this.buildBatchSetPricesInstruction(index, updates, clientTsMs, payer.publicKey, seq));
 
     tx.feePayer = payer.publicKey;
     tx.recentBlockhash = this.getBlockhash();
@@ -137,6 +137,14 @@ export interface PriceUpdateItem {
   priceSource: 'pyth' | 'composite';
 }
 
+/**
+ * One (asset, price) entry of a batch_set_prices instruction
+ */
+export interface AssetPriceI64 {
+  asset: Asset;
+  priceI64: number;
+}
+
 /**
  * CLI configuration options
  */
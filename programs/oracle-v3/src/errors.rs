@@ -11,7 +11,7 @@ pub enum OracleError {
     #[msg("Invalid updater index (must be 1-4)")]
     BadIndex,
 
-    #[msg("Invalid public key")]
+    #[msg("Invalid updater key")]
     BadKey,
 
     #[msg("Asset already registered")]
@@ -34,4 +34,25 @@ pub enum OracleError {
 
     #[msg("Too many assets in batch")]
     TooManyAssets,
+
+    #[msg("Updater slot is already assigned; use rotate_updater")]
+    UpdaterSlotOccupied,
+
+    #[msg("Updater slot is empty")]
+    UpdaterSlotEmpty,
+
+    #[msg("Key is already registered as an updater")]
+    DuplicateUpdater,
+
+    #[msg("Registry still has registered assets")]
+    RegistryNotEmpty,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+
+    #[msg("Write is not newer than the slot's current value")]
+    StaleWrite,
 }
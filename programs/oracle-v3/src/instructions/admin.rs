@@ -0,0 +1,423 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use crate::state::*;
+
+/// Initialize the asset registry (one-time setup)
+pub fn initialize_registry(ctx: Context<InitializeRegistry>, authority: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    registry.authority = authority;
+    registry.asset_count = 0;
+    registry.bump = ctx.bumps.registry;
+    registry.updaters = [Pubkey::default(); 4];
+    registry.pending_authority = Pubkey::default();
+    Ok(())
+}
+
+/// Propose a new registry authority; it takes over once it signs `accept_authority`
+pub fn propose_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.signer.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    require_keys_neq!(new_authority, Pubkey::default(), OracleError::BadKey);
+    registry.pending_authority = new_authority;
+    emit!(AuthorityProposed {
+        authority: registry.authority,
+        pending_authority: new_authority,
+    });
+    Ok(())
+}
+
+/// Complete an authority transfer; signed by the pending authority
+pub fn accept_authority(ctx: Context<TransferAuthority>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    require_keys_neq!(registry.pending_authority, Pubkey::default(), OracleError::NoPendingAuthority);
+    require_keys_eq!(
+        ctx.accounts.signer.key(),
+        registry.pending_authority,
+        OracleError::NotPendingAuthority
+    );
+
+    let old_authority = registry.authority;
+    registry.authority = registry.pending_authority;
+    registry.pending_authority = Pubkey::default();
+    emit!(AuthorityTransferred {
+        old_authority,
+        new_authority: registry.authority,
+    });
+    Ok(())
+}
+
+/// Withdraw a pending authority proposal
+pub fn cancel_authority_transfer(ctx: Context<TransferAuthority>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.signer.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    require_keys_neq!(registry.pending_authority, Pubkey::default(), OracleError::NoPendingAuthority);
+    let pending_authority = registry.pending_authority;
+    registry.pending_authority = Pubkey::default();
+    emit!(AuthorityTransferCancelled {
+        authority: registry.authority,
+        pending_authority,
+    });
+    Ok(())
+}
+
+/// Register a new asset in the oracle
+pub fn register_asset(
+    ctx: Context<RegisterAsset>,
+    mint: Pubkey,
+    symbol: String,
+    decimals: u8,
+    pyth_feed_id: Option<[u8; 32]>,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let asset_config = &mut ctx.accounts.asset_config;
+    let mut price_data = ctx.accounts.price_data.load_init()?;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    // Validate symbol length
+    require!(symbol.len() <= 16, OracleError::SymbolTooLong);
+
+    // Initialize asset config
+    asset_config.mint = mint;
+
+    let mut symbol_bytes = [0u8; 16];
+    symbol_bytes[..symbol.len()].copy_from_slice(symbol.as_bytes());
+    asset_config.symbol = symbol_bytes;
+
+    asset_config.decimals = decimals;
+    asset_config.is_active = true;
+    asset_config.pyth_feed_id = pyth_feed_id;
+    asset_config.added_at = Clock::get()?.unix_timestamp;
+    asset_config.bump = ctx.bumps.asset_config;
+
+    // Initialize price data
+    price_data.mint = mint;
+    price_data.prices = Triplet::default();
+    price_data.last_update = 0;
+    price_data.bump = ctx.bumps.price_data;
+
+    // Increment asset count
+    registry.asset_count = registry.asset_count.checked_add(1).unwrap();
+
+    msg!("Registered asset: {} ({})", symbol, mint);
+
+    Ok(())
+}
+
+/// Deactivate an asset (stops price updates)
+pub fn deactivate_asset(ctx: Context<UpdateAssetConfig>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let asset_config = &mut ctx.accounts.asset_config;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    asset_config.is_active = false;
+
+    msg!("Deactivated asset: {}", asset_config.symbol_as_string());
+
+    Ok(())
+}
+
+/// Activate an asset (resumes price updates)
+pub fn activate_asset(ctx: Context<UpdateAssetConfig>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+    let asset_config = &mut ctx.accounts.asset_config;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    asset_config.is_active = true;
+
+    msg!("Activated asset: {}", asset_config.symbol_as_string());
+
+    Ok(())
+}
+
+/// Remove an asset, closing its config and price accounts
+pub fn deregister_asset(ctx: Context<DeregisterAsset>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    registry.asset_count = registry.asset_count.saturating_sub(1);
+
+    msg!(
+        "Deregistered asset: {} ({})",
+        ctx.accounts.asset_config.symbol_as_string(),
+        ctx.accounts.asset_config.mint
+    );
+
+    Ok(())
+}
+
+/// Close the registry once every asset has been deregistered
+pub fn close_registry(ctx: Context<CloseRegistry>) -> Result<()> {
+    let registry = &ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    require!(registry.asset_count == 0, OracleError::RegistryNotEmpty);
+
+    msg!("Closed asset registry");
+
+    Ok(())
+}
+
+/// Assign a key to an empty updater slot
+pub fn add_updater(ctx: Context<ManageUpdaters>, index: u8, updater: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    require_keys_eq!(registry.updater(index)?, Pubkey::default(), OracleError::UpdaterSlotOccupied);
+    require_keys_neq!(updater, Pubkey::default(), OracleError::BadKey);
+    replace_updater(registry, index, updater)
+}
+
+/// Clear an updater slot
+pub fn remove_updater(ctx: Context<ManageUpdaters>, index: u8) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    require_keys_neq!(registry.updater(index)?, Pubkey::default(), OracleError::UpdaterSlotEmpty);
+    replace_updater(registry, index, Pubkey::default())
+}
+
+/// Replace the key of an assigned updater slot
+pub fn rotate_updater(ctx: Context<ManageUpdaters>, index: u8, new_updater: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+
+    // Validate authority
+    require_keys_eq!(
+        ctx.accounts.authority.key(),
+        registry.authority,
+        OracleError::Unauthorized
+    );
+
+    require_keys_neq!(registry.updater(index)?, Pubkey::default(), OracleError::UpdaterSlotEmpty);
+    require_keys_neq!(new_updater, Pubkey::default(), OracleError::BadKey);
+    replace_updater(registry, index, new_updater)
+}
+
+fn replace_updater(registry: &mut AssetRegistry, index: u8, new_updater: Pubkey) -> Result<()> {
+    let old_updater = registry.set_updater(index, new_updater)?;
+    emit!(UpdaterChanged {
+        index,
+        old_updater,
+        new_updater,
+    });
+    Ok(())
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct UpdaterChanged {
+    pub index: u8,
+    pub old_updater: Pubkey,
+    pub new_updater: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRegistry<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AssetRegistry::SIZE,
+        seeds = [AssetRegistry::SEED],
+        bump
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::OracleV3>,
+
+    /// Only the upgrade authority may create the registry
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ OracleError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub upgrade_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RegisterAsset<'info> {
+    #[account(mut, seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + AssetConfig::SIZE,
+        seeds = [AssetConfig::SEED, mint.as_ref()],
+        bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PriceData::SIZE,
+        seeds = [PriceData::SEED, mint.as_ref()],
+        bump
+    )]
+    pub price_data: AccountLoader<'info, PriceData>,
+
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAssetConfig<'info> {
+    #[account(seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
+    #[account(
+        mut,
+        seeds = [AssetConfig::SEED, asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterAsset<'info> {
+    #[account(mut, seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [AssetConfig::SEED, asset_config.mint.as_ref()],
+        bump = asset_config.bump
+    )]
+    pub asset_config: Account<'info, AssetConfig>,
+
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [PriceData::SEED, asset_config.mint.as_ref()],
+        bump = price_data.load()?.bump
+    )]
+    pub price_data: AccountLoader<'info, PriceData>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the rent of the closed accounts
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRegistry<'info> {
+    #[account(
+        mut,
+        close = recipient,
+        seeds = [AssetRegistry::SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, AssetRegistry>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the rent of the closed registry
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageUpdaters<'info> {
+    #[account(mut, seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferAuthority<'info> {
+    #[account(mut, seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
+    /// Current authority to propose or cancel, pending authority to accept
+    pub signer: Signer<'info>,
+}
@@ -1,13 +1,6 @@
 use anchor_lang::prelude::*;
 use crate::errors::OracleError;
 use crate::state::*;
-use std::str::FromStr;
-
-// Hard-coded per-parameter updaters (mainnet relays)
-const PARAM1_UPDATER: &str = "CGLezzdUpYmxiq3g5xdXxry8SWqwQbSxFJsdqfM13ro9";
-const PARAM2_UPDATER: &str = "FprJrTPJq9eKsVxEVhQCyRChEMaYzyTwcnK8aNfCae2D";
-const PARAM3_UPDATER: &str = "7FZvQQE1VDq2fFSuBmCCxmo8tPNm9LfYqF9BMkbyp1by";
-const PARAM4_UPDATER: &str = "55MyuYePgkwAExNqtdNY4zahSyiM3stjjRm3Ym36sTA8";
 
 /// Update price for a single asset
 pub fn set_price(
@@ -17,7 +10,7 @@ pub fn set_price(
     client_ts_ms: i64,
 ) -> Result<()> {
     let asset_config = &ctx.accounts.asset_config;
-    let price_data = &mut ctx.accounts.price_data;
+    let mut price_data = ctx.accounts.price_data.load_mut()?;
     let signer = ctx.accounts.signer.key();
 
     // Validate asset is active
@@ -27,35 +20,11 @@ pub fn set_price(
     require!(price > 0, OracleError::InvalidPrice);
 
     // Validate signer matches index
-    let expected = match index {
-        1 => Pubkey::from_str(PARAM1_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        2 => Pubkey::from_str(PARAM2_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        3 => Pubkey::from_str(PARAM3_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        4 => Pubkey::from_str(PARAM4_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        _ => return err!(OracleError::BadIndex),
-    };
+    let expected = ctx.accounts.registry.updater(index)?;
     require_keys_eq!(signer, expected, OracleError::UnauthorizedForIndex);
 
     // Update price based on index
-    match index {
-        1 => {
-            price_data.prices.param1 = price;
-            price_data.prices.ts1 = client_ts_ms;
-        }
-        2 => {
-            price_data.prices.param2 = price;
-            price_data.prices.ts2 = client_ts_ms;
-        }
-        3 => {
-            price_data.prices.param3 = price;
-            price_data.prices.ts3 = client_ts_ms;
-        }
-        4 => {
-            price_data.prices.param4 = price;
-            price_data.prices.ts4 = client_ts_ms;
-        }
-        _ => unreachable!(),
-    }
+    price_data.prices.write(index, price, client_ts_ms)?;
 
     price_data.last_update = client_ts_ms;
 
@@ -73,7 +42,7 @@ pub fn set_price(
 
 /// Batch update prices for multiple assets
 pub fn batch_set_prices<'info>(
-    ctx: Context<'_, '_, '_, 'info, BatchSetPrices<'info>>,
+    ctx: Context<'_, '_, 'info, 'info, BatchSetPrices<'info>>,
     index: u8,
     updates: Vec<PriceUpdate>,
     client_ts_ms: i64,
@@ -81,13 +50,7 @@ pub fn batch_set_prices<'info>(
     let signer = ctx.accounts.signer.key();
 
     // Validate signer matches index
-    let expected = match index {
-        1 => Pubkey::from_str(PARAM1_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        2 => Pubkey::from_str(PARAM2_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        3 => Pubkey::from_str(PARAM3_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        4 => Pubkey::from_str(PARAM4_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-        _ => return err!(OracleError::BadIndex),
-    };
+    let expected = ctx.accounts.registry.updater(index)?;
     require_keys_eq!(signer, expected, OracleError::UnauthorizedForIndex);
 
     // Validate batch size
@@ -110,8 +73,9 @@ pub fn batch_set_prices<'info>(
         let price_data_info = &ctx.remaining_accounts[i * 2 + 1];
 
         // Deserialize accounts
-        let mut asset_config = Account::<AssetConfig>::try_from(asset_config_info)?;
-        let mut price_data = Account::<PriceData>::try_from(price_data_info)?;
+        let asset_config = Account::<AssetConfig>::try_from(asset_config_info)?;
+        let price_data_loader = AccountLoader::<PriceData>::try_from(price_data_info)?;
+        let mut price_data = price_data_loader.load_mut()?;
 
         // Validate accounts match the mint
         require_keys_eq!(asset_config.mint, update.mint, OracleError::AssetNotFound);
@@ -121,31 +85,10 @@ pub fn batch_set_prices<'info>(
         require!(asset_config.is_active, OracleError::AssetInactive);
 
         // Update price based on index
-        match index {
-            1 => {
-                price_data.prices.param1 = update.price;
-                price_data.prices.ts1 = client_ts_ms;
-            }
-            2 => {
-                price_data.prices.param2 = update.price;
-                price_data.prices.ts2 = client_ts_ms;
-            }
-            3 => {
-                price_data.prices.param3 = update.price;
-                price_data.prices.ts3 = client_ts_ms;
-            }
-            4 => {
-                price_data.prices.param4 = update.price;
-                price_data.prices.ts4 = client_ts_ms;
-            }
-            _ => unreachable!(),
-        }
+        price_data.prices.write(index, update.price, client_ts_ms)?;
 
         price_data.last_update = client_ts_ms;
 
-        // Serialize back
-        price_data.exit(&ctx.program_id)?;
-
         emit!(PriceUpdated {
             mint: update.mint,
             index,
@@ -177,6 +120,9 @@ pub struct PriceUpdated {
 
 #[derive(Accounts)]
 pub struct SetPrice<'info> {
+    #[account(seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
     #[account(
         seeds = [AssetConfig::SEED, asset_config.mint.as_ref()],
         bump = asset_config.bump
@@ -185,16 +131,19 @@ pub struct SetPrice<'info> {
 
     #[account(
         mut,
-        seeds = [PriceData::SEED, price_data.mint.as_ref()],
-        bump = price_data.bump
+        seeds = [PriceData::SEED, asset_config.mint.as_ref()],
+        bump = price_data.load()?.bump
     )]
-    pub price_data: Account<'info, PriceData>,
+    pub price_data: AccountLoader<'info, PriceData>,
 
     pub signer: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct BatchSetPrices<'info> {
+    #[account(seeds = [AssetRegistry::SEED], bump = registry.bump)]
+    pub registry: Account<'info, AssetRegistry>,
+
     pub signer: Signer<'info>,
     // Remaining accounts passed dynamically:
     // [asset_config, price_data, asset_config, price_data, ...]
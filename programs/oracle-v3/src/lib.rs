@@ -0,0 +1,103 @@
+// anchor 0.31's #[program] expansion still calls AccountInfo::realloc
+#![allow(deprecated)]
+
+use anchor_lang::prelude::*;
+
+pub mod errors;
+pub mod instructions;
+pub mod state;
+
+use instructions::*;
+
+declare_id!("8gLZV8k3R6JrAs5BZzyyZQikjEfqvJjAz8PxbiYmz2Kb");
+
+#[program]
+pub mod oracle_v3 {
+    use super::*;
+
+    /// Initialize the asset registry (one-time setup, upgrade authority only)
+    pub fn initialize_registry(ctx: Context<InitializeRegistry>, authority: Pubkey) -> Result<()> {
+        instructions::initialize_registry(ctx, authority)
+    }
+
+    /// Propose a new registry authority
+    pub fn propose_authority(ctx: Context<TransferAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority(ctx, new_authority)
+    }
+
+    /// Accept a proposed authority transfer (signed by the new authority)
+    pub fn accept_authority(ctx: Context<TransferAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    /// Cancel a pending authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<TransferAuthority>) -> Result<()> {
+        instructions::cancel_authority_transfer(ctx)
+    }
+
+    /// Register a new asset in the oracle
+    pub fn register_asset(
+        ctx: Context<RegisterAsset>,
+        mint: Pubkey,
+        symbol: String,
+        decimals: u8,
+        pyth_feed_id: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::register_asset(ctx, mint, symbol, decimals, pyth_feed_id)
+    }
+
+    /// Deactivate an asset (stops price updates)
+    pub fn deactivate_asset(ctx: Context<UpdateAssetConfig>) -> Result<()> {
+        instructions::deactivate_asset(ctx)
+    }
+
+    /// Activate an asset (resumes price updates)
+    pub fn activate_asset(ctx: Context<UpdateAssetConfig>) -> Result<()> {
+        instructions::activate_asset(ctx)
+    }
+
+    /// Remove an asset and close its accounts
+    pub fn deregister_asset(ctx: Context<DeregisterAsset>) -> Result<()> {
+        instructions::deregister_asset(ctx)
+    }
+
+    /// Close the registry (requires every asset to be deregistered)
+    pub fn close_registry(ctx: Context<CloseRegistry>) -> Result<()> {
+        instructions::close_registry(ctx)
+    }
+
+    /// Assign a key to an empty updater slot
+    pub fn add_updater(ctx: Context<ManageUpdaters>, index: u8, updater: Pubkey) -> Result<()> {
+        instructions::add_updater(ctx, index, updater)
+    }
+
+    /// Clear an updater slot
+    pub fn remove_updater(ctx: Context<ManageUpdaters>, index: u8) -> Result<()> {
+        instructions::remove_updater(ctx, index)
+    }
+
+    /// Replace the key of an assigned updater slot
+    pub fn rotate_updater(ctx: Context<ManageUpdaters>, index: u8, new_updater: Pubkey) -> Result<()> {
+        instructions::rotate_updater(ctx, index, new_updater)
+    }
+
+    /// Update price for a single asset
+    pub fn set_price(
+        ctx: Context<SetPrice>,
+        index: u8,
+        price: i64,
+        client_ts_ms: i64,
+    ) -> Result<()> {
+        instructions::set_price(ctx, index, price, client_ts_ms)
+    }
+
+    /// Batch update prices for multiple assets
+    pub fn batch_set_prices<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchSetPrices<'info>>,
+        index: u8,
+        updates: Vec<PriceUpdate>,
+        client_ts_ms: i64,
+    ) -> Result<()> {
+        instructions::batch_set_prices(ctx, index, updates, client_ts_ms)
+    }
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+
+/// Price data for a specific asset (one per asset). Zero-copy with a fixed
+/// `#[repr(C)]` layout so readers can cast the account bytes directly.
+#[account(zero_copy)]
+pub struct PriceData {
+    /// Links back to the asset mint
+    pub mint: Pubkey,
+    /// Price data from 4 updaters
+    pub prices: Triplet,
+    /// Last update timestamp
+    pub last_update: i64,
+    /// PDA bump
+    pub bump: u8,
+    pub _padding: [u8; 7],
+}
+
+impl PriceData {
+    pub const SIZE: usize = std::mem::size_of::<PriceData>(); // 112
+    pub const SEED: &'static [u8] = b"price_data";
+}
+
+/// Stores prices from 4 independent updaters with timestamps
+#[zero_copy]
+#[derive(Default)]
+pub struct Triplet {
+    pub param1: i64,
+    pub param2: i64,
+    pub param3: i64,
+    pub param4: i64,
+    pub ts1: i64,
+    pub ts2: i64,
+    pub ts3: i64,
+    pub ts4: i64,
+}
+
+impl Triplet {
+    pub const SIZE: usize = 8 * 8; // 64 bytes
+
+    /// Store updater `index`'s price. The write must carry a newer timestamp
+    /// than the slot holds, so delayed or replayed updates cannot roll it back.
+    pub fn write(&mut self, index: u8, price: i64, client_ts_ms: i64) -> Result<()> {
+        let (param, ts) = match index {
+            1 => (&mut self.param1, &mut self.ts1),
+            2 => (&mut self.param2, &mut self.ts2),
+            3 => (&mut self.param3, &mut self.ts3),
+            4 => (&mut self.param4, &mut self.ts4),
+            _ => return err!(OracleError::BadIndex),
+        };
+        require!(client_ts_ms > *ts, OracleError::StaleWrite);
+        *param = price;
+        *ts = client_ts_ms;
+        Ok(())
+    }
+}
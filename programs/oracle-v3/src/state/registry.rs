@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+
+/// Global asset registry account
+#[account]
+pub struct AssetRegistry {
+    /// Authority that can add new assets
+    pub authority: Pubkey,
+    /// Total number of registered assets
+    pub asset_count: u32,
+    /// PDA bump
+    pub bump: u8,
+    /// Keys allowed to write each updater slot (index 1-4); default = empty
+    pub updaters: [Pubkey; 4],
+    /// Authority proposed by `propose_authority`; default when none is pending
+    pub pending_authority: Pubkey,
+}
+
+impl AssetRegistry {
+    pub const SIZE: usize = 32 + 4 + 1 + (32 * 4) + 32;
+    pub const SEED: &'static [u8] = b"registry";
+
+    /// Updater registered for slot `index`; `Pubkey::default()` when the slot is empty
+    pub fn updater(&self, index: u8) -> Result<Pubkey> {
+        require!((1..=4).contains(&index), OracleError::BadIndex);
+        Ok(self.updaters[index as usize - 1])
+    }
+
+    /// Replace the updater for slot `index`, returning the previous key
+    pub fn set_updater(&mut self, index: u8, new_updater: Pubkey) -> Result<Pubkey> {
+        let old_updater = self.updater(index)?;
+        if new_updater != Pubkey::default() {
+            require!(!self.updaters.contains(&new_updater), OracleError::DuplicateUpdater);
+        }
+        self.updaters[index as usize - 1] = new_updater;
+        Ok(old_updater)
+    }
+}
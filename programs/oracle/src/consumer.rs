@@ -0,0 +1,185 @@
+//! Read helpers for programs consuming oracle prices.
+//!
+//! Depend on this crate with `features = ["cpi"]` (or `no-entrypoint`), pass
+//! the `state_v2` account into your instruction and call [`get_price`]. The
+//! account's owner, PDA address and discriminator are all verified, so the
+//! account does not need any constraints on the consumer side.
+//!
+//! Reads fail with `OraclePaused` while the oracle is paused and with
+//! `AssetHalted` for halted assets. Settlement-only assets are still served;
+//! check [`Price::status`] before opening new positions against them.
+
+use anchor_lang::prelude::*;
+use std::cell::Ref;
+use std::ops::Deref;
+use crate::errors::OracleError;
+use crate::state::*;
+
+/// Aggregated price of one asset, validated for freshness
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Price {
+    pub price: i64,
+    /// Median absolute deviation of the contributing updater slots
+    pub conf: u64,
+    /// Decimal exponent: real price = `price * 10^expo`
+    pub expo: i32,
+    /// Cluster unix_timestamp at which the price was aggregated
+    pub publish_time: i64,
+    /// Number of updater slots that contributed
+    pub num_contributors: u8,
+    /// Updater slots left out as outliers, bit `index - 1` per slot
+    pub excluded: u8,
+    /// Trading status of the asset; never `Halted`
+    pub status: AssetStatus,
+}
+
+/// Latest round of an asset, with Chainlink `latestRoundData` semantics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundData {
+    /// Latest round opened; may still be collecting submissions
+    pub round_id: u64,
+    /// Median of the agreeing submissions of round `answered_in_round`
+    pub answer: i64,
+    /// Decimal exponent: real price = `answer * 10^expo`
+    pub expo: i32,
+    /// Cluster unix_timestamp round `answered_in_round` opened
+    pub started_at: i64,
+    /// Cluster unix_timestamp round `answered_in_round` finalized
+    pub updated_at: i64,
+    /// Round the answer was computed in; behind `round_id` while a newer
+    /// round is open or after one was abandoned
+    pub answered_in_round: u64,
+}
+
+/// Snapshot of an asset's TWAP accumulator. Take one now and keep it (or
+/// read one back from your own account later) and pass both to [`twap`].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Observation {
+    /// Wrapping sum of price * seconds, in the asset's decimals
+    pub cum_price: i64,
+    /// Cluster unix_timestamp the accumulator is valid at
+    pub timestamp: i64,
+}
+
+/// Address of the `state_v2` PDA
+pub fn state_address() -> Pubkey {
+    Pubkey::find_program_address(&[State::SEED], &crate::ID).0
+}
+
+/// Borrow a state account after checking it is the genuine `state_v2` PDA
+/// owned by this program.
+pub fn load_state<'a>(state_account: &'a AccountInfo) -> Result<StateAccount<Ref<'a, [u8]>>> {
+    require_keys_eq!(*state_account.owner, crate::ID, OracleError::BadStateAccount);
+    let state = StateAccount::load(state_account)?;
+    let expected = Pubkey::create_program_address(&[State::SEED, &[state.header().bump]], &crate::ID)
+        .map_err(|_| error!(OracleError::BadStateAccount))?;
+    require_keys_eq!(*state_account.key, expected, OracleError::BadStateAccount);
+    Ok(state)
+}
+
+/// Asset row of `asset`, refused when the oracle is paused or the asset halted.
+fn servable_asset<D: Deref<Target = [u8]>>(state: &StateAccount<D>, asset: u8) -> Result<&AssetEntry> {
+    require!(state.header().paused == 0, OracleError::OraclePaused);
+    let entry = state.asset(asset)?;
+    require!(!entry.is_halted(), OracleError::AssetHalted);
+    Ok(entry)
+}
+
+/// Aggregated price of `asset`, rejected with `StalePrice` when it is older
+/// than `max_age_secs` by the cluster clock.
+pub fn get_price(state_account: &AccountInfo, asset: impl Into<u8>, max_age_secs: u64) -> Result<Price> {
+    let state = load_state(state_account)?;
+    let clock = Clock::get()?;
+    let entry = servable_asset(&state, asset.into())?;
+    let prices = &entry.prices;
+    Ok(Price {
+        price: prices.get_price_no_older_than(&clock, max_age_secs)?,
+        conf: prices.agg_conf,
+        expo: -(entry.decimals as i32),
+        publish_time: prices.agg_ts,
+        num_contributors: prices.agg_count,
+        excluded: prices.excluded,
+        status: entry.status(),
+    })
+}
+
+/// Time-weighted EMA of the price and confidence of `asset`, rejected with
+/// `StalePrice` when the underlying aggregate is older than `max_age_secs`.
+pub fn get_ema_price(state_account: &AccountInfo, asset: impl Into<u8>, max_age_secs: u64) -> Result<Price> {
+    let state = load_state(state_account)?;
+    let clock = Clock::get()?;
+    let entry = servable_asset(&state, asset.into())?;
+    let prices = &entry.prices;
+    prices.get_price_no_older_than(&clock, max_age_secs)?;
+    Ok(Price {
+        price: prices.ema_price,
+        conf: prices.ema_conf,
+        expo: -(entry.decimals as i32),
+        publish_time: prices.agg_ts,
+        num_contributors: prices.agg_count,
+        excluded: prices.excluded,
+        status: entry.status(),
+    })
+}
+
+/// Latest round of `asset`, rejected with `NoRoundData` until a round has
+/// finalized. As with Chainlink, freshness is up to the caller: compare
+/// `updated_at` with the clock and `answered_in_round` with `round_id`.
+pub fn latest_round_data(state_account: &AccountInfo, asset: impl Into<u8>) -> Result<RoundData> {
+    let state = load_state(state_account)?;
+    let entry = servable_asset(&state, asset.into())?;
+    let round = &entry.round;
+    require!(round.answered_in_round > 0, OracleError::NoRoundData);
+    Ok(RoundData {
+        round_id: round.round_id,
+        answer: round.answer,
+        expo: -(entry.decimals as i32),
+        started_at: round.answer_started_at,
+        updated_at: round.updated_at,
+        answered_in_round: round.answered_in_round,
+    })
+}
+
+/// Current TWAP accumulator of `asset`, extrapolated to the cluster clock.
+/// Rejected with `StalePrice` when the aggregate is older than `max_age_secs`,
+/// since the extrapolation assumes the last price still holds.
+pub fn get_observation(state_account: &AccountInfo, asset: impl Into<u8>, max_age_secs: u64) -> Result<Observation> {
+    let state = load_state(state_account)?;
+    let clock = Clock::get()?;
+    let prices = &servable_asset(&state, asset.into())?.prices;
+    prices.get_price_no_older_than(&clock, max_age_secs)?;
+    Ok(Observation {
+        cum_price: prices.cumulative_at(clock.unix_timestamp),
+        timestamp: clock.unix_timestamp,
+    })
+}
+
+/// Time-weighted average price between two observations of the same asset,
+/// or `None` when `end` is not later than `start`.
+pub fn twap(start: &Observation, end: &Observation) -> Option<i64> {
+    let elapsed = end.timestamp.checked_sub(start.timestamp).filter(|&e| e > 0)?;
+    Some(end.cum_price.wrapping_sub(start.cum_price) / elapsed)
+}
+
+/// Address of the `PriceHistory` PDA of `asset`
+pub fn history_address(asset: impl Into<u8>) -> Pubkey {
+    Pubkey::find_program_address(&[PriceHistory::SEED, &[asset.into()]], &crate::ID).0
+}
+
+/// Address of the Pyth `PriceUpdateV2`-layout mirror of `asset`
+pub fn pyth_mirror_address(asset: impl Into<u8>) -> Pubkey {
+    Pubkey::find_program_address(&[PriceUpdateV2::SEED, &[asset.into()]], &crate::ID).0
+}
+
+/// Borrow the price history of `asset` after checking the account is a
+/// `PriceHistory` owned by this program and recording that asset. Walk it
+/// with [`PriceHistory::iter`], newest first.
+pub fn load_history<'a>(history_account: &'a AccountInfo, asset: impl Into<u8>) -> Result<Ref<'a, PriceHistory>> {
+    require_keys_eq!(*history_account.owner, crate::ID, OracleError::BadHistoryAccount);
+    let data = history_account.try_borrow_data()?;
+    require!(data.len() >= 8 + PriceHistory::SIZE, OracleError::BadHistoryAccount);
+    require!(data[..8] == *PriceHistory::DISCRIMINATOR, OracleError::BadHistoryAccount);
+    let history = Ref::map(data, |d| bytemuck::from_bytes::<PriceHistory>(&d[8..8 + PriceHistory::SIZE]));
+    require!(history.asset == asset.into(), OracleError::BadHistoryAccount);
+    Ok(history)
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum OracleError {
+    #[msg("Unauthorized (admin)")]
+    Unauthorized,
+    #[msg("Unknown asset id")]
+    BadAsset,
+    #[msg("Index must be 1, 2, 3, or 4")]
+    BadIndex,
+    #[msg("Signer not authorized for the requested index")]
+    UnauthorizedForIndex,
+    #[msg("Invalid updater key")]
+    BadKey,
+    #[msg("Minimum quorum must be between 1 and 4")]
+    BadQuorum,
+    #[msg("Staleness window must be greater than zero")]
+    BadStaleness,
+    #[msg("Clock drift tolerance must be greater than zero")]
+    BadClockDrift,
+    #[msg("client_ts_ms drifts too far from the cluster clock")]
+    ClockDrift,
+    #[msg("Price is stale")]
+    StalePrice,
+    #[msg("Updater slot is already assigned; use rotate_updater")]
+    UpdaterSlotOccupied,
+    #[msg("Updater slot is empty")]
+    UpdaterSlotEmpty,
+    #[msg("Key is already registered as an updater")]
+    DuplicateUpdater,
+    #[msg("Account is not an oracle State account")]
+    BadStateAccount,
+    #[msg("Asset id already registered")]
+    AssetAlreadyRegistered,
+    #[msg("Asset symbol already registered")]
+    DuplicateSymbol,
+    #[msg("Symbol must be 1-16 bytes")]
+    BadSymbol,
+    #[msg("Batch contains no updates")]
+    EmptyBatch,
+    #[msg("Asset listed more than once in batch")]
+    DuplicateAsset,
+    #[msg("Too many assets in query")]
+    TooManyAssets,
+    #[msg("Decimals must be at most 18")]
+    BadDecimals,
+    #[msg("Rescaled price does not fit in 64 bits")]
+    RescaleOverflow,
+    #[msg("Asset has price history enabled")]
+    HistoryEnabled,
+    #[msg("Asset has no price history")]
+    HistoryDisabled,
+    #[msg("Missing price history account")]
+    MissingHistoryAccount,
+    #[msg("Price history account does not belong to this asset")]
+    BadHistoryAccount,
+    #[msg("EMA period must be non-zero")]
+    BadEmaPeriod,
+    #[msg("Price must be positive")]
+    BadPrice,
+    #[msg("Price moved further than the asset's circuit breaker allows")]
+    PriceMoveTooLarge,
+    #[msg("Asset is halted")]
+    AssetHalted,
+    #[msg("Asset is not halted")]
+    AssetNotHalted,
+    #[msg("A window limit needs a non-zero window")]
+    BadBreakerConfig,
+    #[msg("Oracle is paused")]
+    OraclePaused,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("Action needs an account that was not provided")]
+    MissingActionAccount,
+    #[msg("Action can only be carried out through governance")]
+    GovernanceOnly,
+    #[msg("Signer set must hold 1-10 distinct keys")]
+    BadSignerSet,
+    #[msg("Threshold must be between 1 and the number of signers")]
+    BadThreshold,
+    #[msg("Signer is not a governance signer")]
+    NotGovernanceSigner,
+    #[msg("Signer already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal does not have enough approvals")]
+    ThresholdNotMet,
+    #[msg("Governance signers changed since the proposal was created")]
+    StaleProposal,
+    #[msg("Governance is not the state's update authority")]
+    NotGovernanceAuthority,
+    #[msg("Action is timelocked; queue it with queue_action")]
+    TimelockRequired,
+    #[msg("Timelock has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("Timelock must be at most 30 days")]
+    BadTimelock,
+    #[msg("Expected an Ed25519 sig-verify instruction right before this one")]
+    MissingSigVerify,
+    #[msg("Ed25519 instruction does not cover this message and updater")]
+    BadSigVerify,
+    #[msg("Write is older than the slot's current value or reuses a sequence number")]
+    StaleWrite,
+    #[msg("Message slot is ahead of the cluster")]
+    FutureSlot,
+    #[msg("Round needs between 0 and 4 answers")]
+    BadRoundConfig,
+    #[msg("Asset has no finalized round")]
+    NoRoundData,
+    #[msg("Asset has a Pyth mirror attached")]
+    PythMirrorEnabled,
+    #[msg("Asset has no Pyth mirror")]
+    PythMirrorDisabled,
+    #[msg("Missing Pyth mirror account")]
+    MissingPythMirror,
+    #[msg("Pyth mirror account does not belong to this asset")]
+    BadPythMirror,
+    #[msg("Proposal is past its ETA and can no longer be vetoed")]
+    VetoClosed,
+    #[msg("Action is not timelocked and cannot be vetoed")]
+    NotVetoable,
+    #[msg("Signer already vetoed this proposal")]
+    AlreadyVetoed,
+    #[msg("Governance is already the update authority; change it with SetGovernance")]
+    GovernanceActive,
+}
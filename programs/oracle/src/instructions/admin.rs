@@ -0,0 +1,752 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount, Transfer};
+use crate::errors::OracleError;
+use crate::state::*;
+use super::governance::create_governance;
+use super::history::{attach_history, detach_history};
+use super::pyth_mirror::{attach_pyth_mirror, detach_pyth_mirror, sync_mirror_status};
+use std::ops::{Deref, DerefMut};
+
+// Aggregation defaults applied by `initialize`
+const DEFAULT_MIN_QUORUM: u8 = 2;
+const DEFAULT_STALE_AFTER_SECS: u32 = 30;
+const DEFAULT_MAX_CLOCK_DRIFT_MS: u32 = 10_000;
+const DEFAULT_EMA_PERIOD_SECS: u32 = 300;
+// Decimals of the assets seeded by `initialize`
+const DEFAULT_DECIMALS: u8 = 6;
+
+/// Create the state account, seeded with the default assets. Gated on the
+/// program upgrade authority by the `Initialize` constraints.
+pub fn initialize(ctx: Context<Initialize>, update_authority: Pubkey) -> Result<()> {
+    {
+        let s = &mut ctx.accounts.state.load_init()?;
+        s.update_authority = update_authority;
+        s.bump = ctx.bumps.state;
+        s.min_quorum = DEFAULT_MIN_QUORUM;
+        s.stale_after_secs = DEFAULT_STALE_AFTER_SECS;
+        s.max_clock_drift_ms = DEFAULT_MAX_CLOCK_DRIFT_MS;
+        s.ema_period_secs = DEFAULT_EMA_PERIOD_SECS;
+        s.updaters = [Pubkey::default(); 4];
+        s.asset_count = Asset::ALL.len() as u32;
+    }
+
+    // The discriminator is only written on exit, so fill the table directly
+    let info = ctx.accounts.state.to_account_info();
+    let mut data = info.try_borrow_mut_data()?;
+    let assets: &mut [AssetEntry] = bytemuck::cast_slice_mut(&mut data[State::HEADER_END..]);
+    for (entry, asset) in assets.iter_mut().zip(Asset::ALL) {
+        *entry = AssetEntry::new(asset as u8, asset.symbol(), DEFAULT_DECIMALS)?;
+    }
+    Ok(())
+}
+
+/// Propose `new_auth` as the next admin; it takes over once it signs
+/// `accept_authority`. Replaces any earlier proposal.
+pub fn propose_authority(ctx: Context<SetUpdateAuthority>, new_auth: Pubkey) -> Result<()> {
+    ctx.accounts.run(AdminAction::ProposeAuthority { new_authority: new_auth })
+}
+
+/// Complete a transfer; must be signed by the pending authority
+pub fn accept_authority(ctx: Context<SetUpdateAuthority>) -> Result<()> {
+    let s = &mut *ctx.accounts.state.load_mut()?;
+    require_keys_neq!(s.pending_authority, Pubkey::default(), OracleError::NoPendingAuthority);
+    require_keys_eq!(ctx.accounts.signer.key(), s.pending_authority, OracleError::NotPendingAuthority);
+    let old_authority = s.update_authority;
+    s.update_authority = s.pending_authority;
+    s.pending_authority = Pubkey::default();
+    emit!(AuthorityTransferred {
+        old_authority,
+        new_authority: s.update_authority,
+    });
+    Ok(())
+}
+
+/// Withdraw a pending proposal
+pub fn cancel_authority_transfer(ctx: Context<SetUpdateAuthority>) -> Result<()> {
+    ctx.accounts.run(AdminAction::CancelAuthorityTransfer)
+}
+
+pub fn set_aggregation_config(
+    ctx: Context<SetUpdateAuthority>,
+    min_quorum: u8,
+    stale_after_secs: u32,
+) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetAggregationConfig { min_quorum, stale_after_secs })
+}
+
+pub fn set_max_clock_drift(ctx: Context<SetUpdateAuthority>, max_clock_drift_ms: u32) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetMaxClockDrift { max_clock_drift_ms })
+}
+
+pub fn set_ema_period(ctx: Context<SetUpdateAuthority>, ema_period_secs: u32) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetEmaPeriod { ema_period_secs })
+}
+
+pub fn add_updater(ctx: Context<SetUpdateAuthority>, index: u8, updater: Pubkey) -> Result<()> {
+    ctx.accounts.run(AdminAction::AddUpdater { index, updater })
+}
+
+pub fn remove_updater(ctx: Context<SetUpdateAuthority>, index: u8) -> Result<()> {
+    ctx.accounts.run(AdminAction::RemoveUpdater { index })
+}
+
+pub fn rotate_updater(ctx: Context<SetUpdateAuthority>, index: u8, new_updater: Pubkey) -> Result<()> {
+    ctx.accounts.run(AdminAction::RotateUpdater { index, new_updater })
+}
+
+/// Append an asset to the table; the account grows by one entry
+pub fn add_asset(ctx: Context<AddAsset>, id: u8, symbol: String, decimals: u8) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let action = AdminAction::AddAsset { id, symbol, decimals };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.payer),
+        system_program: Some(&a.system_program),
+        remaining: &[],
+    };
+    apply_action(&accounts, action)
+}
+
+/// Change the decimals of an asset, rescaling every stored value to match
+pub fn set_asset_decimals(ctx: Context<SetUpdateAuthority>, id: u8, decimals: u8) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetAssetDecimals { id, decimals })
+}
+
+/// Configure an asset's circuit breaker. Limits are in basis points and 0
+/// disables a limit; a breach halts the asset when `halt_on_breach` is set
+/// and is rejected otherwise (dropped, in a batch).
+pub fn set_circuit_breaker(
+    ctx: Context<SetUpdateAuthority>,
+    id: u8,
+    max_move_bps: u16,
+    max_window_move_bps: u16,
+    window_secs: u32,
+    halt_on_breach: bool,
+) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetCircuitBreaker {
+        id,
+        max_move_bps,
+        max_window_move_bps,
+        window_secs,
+        halt_on_breach,
+    })
+}
+
+/// Set how far, in basis points, an updater slot may sit from the median of
+/// the other fresh slots before it is left out of the asset's aggregate; 0
+/// disables outlier rejection
+pub fn set_outlier_threshold(ctx: Context<SetUpdateAuthority>, id: u8, outlier_bps: u16) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetOutlierThreshold { id, outlier_bps })
+}
+
+/// Configure an asset's rounds: a round finalizes once `min_answers`
+/// updaters have submitted prices within `tolerance_bps` of each other; 0
+/// answers turns rounds off
+pub fn set_round_config(ctx: Context<SetUpdateAuthority>, id: u8, min_answers: u8, tolerance_bps: u16) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetRoundConfig { id, min_answers, tolerance_bps })
+}
+
+/// Let updates of a halted asset through again. The breaker window restarts
+/// from the next update; the per-update limit still applies against the last
+/// aggregate, so widen it first if the market really moved that far. Passing
+/// the asset's Pyth mirror in `remaining_accounts` updates it in the same go.
+pub fn resume_asset<'info>(ctx: Context<'_, '_, '_, 'info, SetUpdateAuthority<'info>>, id: u8) -> Result<()> {
+    ctx.accounts.run_with(AdminAction::ResumeAsset { id }, ctx.remaining_accounts)
+}
+
+/// Set an asset's trading status. Passing the asset's Pyth mirror in
+/// `remaining_accounts` updates it in the same go.
+pub fn set_asset_status<'info>(ctx: Context<'_, '_, '_, 'info, SetUpdateAuthority<'info>>, id: u8, status: AssetStatus) -> Result<()> {
+    ctx.accounts.run_with(AdminAction::SetAssetStatus { id, status }, ctx.remaining_accounts)
+}
+
+/// Global emergency stop: while paused no prices are written or served. Pyth
+/// mirrors catch up through `sync_pyth_mirrors`.
+pub fn set_paused(ctx: Context<SetUpdateAuthority>, paused: bool) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetPaused { paused })
+}
+
+/// Set the delay timelocked admin actions have to wait out; 0 disables it
+pub fn set_timelock(ctx: Context<SetUpdateAuthority>, timelock_secs: u32) -> Result<()> {
+    ctx.accounts.run(AdminAction::SetTimelock { timelock_secs })
+}
+
+/// Remove an asset from the table; the account shrinks by one entry and the
+/// freed rent goes to `payer`
+pub fn retire_asset(ctx: Context<RetireAsset>, id: u8) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let action = AdminAction::RetireAsset { id };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.payer),
+        system_program: Some(&a.system_program),
+        remaining: &[],
+    };
+    apply_action(&accounts, action)
+}
+
+/// Close the state account. Every history and Pyth mirror has to be closed
+/// first, or they could never be reclaimed after a re-`initialize`.
+pub fn close_state(ctx: Context<CloseState>) -> Result<()> {
+    let state = ctx.accounts.state.to_account_info();
+
+    // Every State layout so far starts with the discriminator followed by
+    // update_authority, so the admin check works on legacy accounts too.
+    {
+        let data = state.try_borrow_data()?;
+        require!(data.len() >= 8 + 32, OracleError::BadStateAccount);
+        require!(data[..8] == *State::DISCRIMINATOR, OracleError::BadStateAccount);
+        let update_authority = Pubkey::try_from(&data[8..40]).unwrap();
+        require_keys_eq!(ctx.accounts.authority.key(), update_authority, OracleError::Unauthorized);
+
+        // Legacy layouts predate the timelock; only trust the field on an
+        // account of exactly the current size
+        if let Ok(current) = StateAccount::new(&data[..]) {
+            let s = current.header();
+            if data.len() == State::space(s.asset_count as usize) {
+                require!(s.timelock_secs == 0, OracleError::TimelockRequired);
+                require_detached(&current)?;
+            }
+        }
+    }
+
+    close_account(&state, &ctx.accounts.recipient)
+}
+
+/// Check that no asset still has a `PriceHistory` or Pyth mirror attached,
+/// which would be orphaned by closing the state.
+fn require_detached<D: Deref<Target = [u8]>>(state: &StateAccount<D>) -> Result<()> {
+    for entry in state.assets() {
+        require!(entry.has_history == 0, OracleError::HistoryEnabled);
+        require!(entry.has_pyth_mirror == 0, OracleError::PythMirrorEnabled);
+    }
+    Ok(())
+}
+
+/// Check that `signer` is the admin and may run `action` right away.
+pub(crate) fn require_admin(state: &AccountInfo, signer: &Signer, action: &AdminAction) -> Result<()> {
+    StateAccount::load(state)?.header().authorize(&signer.key(), action)
+}
+
+/// Accounts an `AdminAction` may touch
+pub struct ActionAccounts<'a, 'info> {
+    pub state: &'a AccountInfo<'info>,
+    /// Funds growth of the state account and receives its refunds; needed by
+    /// `AddAsset`, `RetireAsset` and `CloseState`
+    pub payer: Option<&'a AccountInfo<'info>>,
+    /// Needed by `AddAsset`, `RetireAsset` and the actions creating accounts
+    pub system_program: Option<&'a AccountInfo<'info>>,
+    /// Accounts the action creates, closes or updates, in order: the history
+    /// account for `InitHistory` and `CloseHistory`, the mirror for
+    /// `InitPythMirror` and `ClosePythMirror`, the governance account for
+    /// `InitGovernance`, and optionally the asset's Pyth mirror for
+    /// `SetAssetStatus` and `ResumeAsset`
+    pub remaining: &'a [AccountInfo<'info>],
+}
+
+/// Carry out an admin action. The caller has already established that it
+/// is authorized, by the admin's signature or by governance approval.
+pub fn apply_action(accounts: &ActionAccounts, action: AdminAction) -> Result<()> {
+    let missing = || error!(OracleError::MissingActionAccount);
+    match action {
+        AdminAction::AddAsset { id, symbol, decimals } => {
+            let payer = accounts.payer.ok_or_else(missing)?;
+            let system_program = accounts.system_program.ok_or_else(missing)?;
+            add_asset_entry(accounts.state, payer, system_program, id, symbol, decimals)
+        }
+        AdminAction::RetireAsset { id } => {
+            let payer = accounts.payer.ok_or_else(missing)?;
+            let system_program = accounts.system_program.ok_or_else(missing)?;
+            retire_asset_entry(accounts.state, payer, system_program, id)
+        }
+        AdminAction::InitHistory { asset } => {
+            let history = accounts.remaining.first().ok_or_else(missing)?;
+            let payer = accounts.payer.ok_or_else(missing)?;
+            let system_program = accounts.system_program.ok_or_else(missing)?;
+            attach_history(accounts.state, history, payer, system_program, asset)
+        }
+        AdminAction::CloseHistory { asset } => {
+            let history = accounts.remaining.first().ok_or_else(missing)?;
+            detach_history(accounts.state, history, accounts.payer.ok_or_else(missing)?, asset)
+        }
+        AdminAction::InitPythMirror { asset, feed_id } => {
+            let mirror = accounts.remaining.first().ok_or_else(missing)?;
+            let payer = accounts.payer.ok_or_else(missing)?;
+            let system_program = accounts.system_program.ok_or_else(missing)?;
+            attach_pyth_mirror(accounts.state, mirror, payer, system_program, asset, feed_id)
+        }
+        AdminAction::ClosePythMirror { asset } => {
+            let mirror = accounts.remaining.first().ok_or_else(missing)?;
+            detach_pyth_mirror(accounts.state, mirror, accounts.payer.ok_or_else(missing)?, asset)
+        }
+        AdminAction::InitGovernance { signers, threshold } => {
+            let governance = accounts.remaining.first().ok_or_else(missing)?;
+            let payer = accounts.payer.ok_or_else(missing)?;
+            let system_program = accounts.system_program.ok_or_else(missing)?;
+            create_governance(accounts.state, governance, payer, system_program, signers, threshold)
+        }
+        AdminAction::CloseState => {
+            require_detached(&StateAccount::load(accounts.state)?)?;
+            close_account(accounts.state, accounts.payer.ok_or_else(missing)?)
+        }
+        AdminAction::SetGovernance { .. } => err!(OracleError::GovernanceOnly),
+        AdminAction::SetAssetStatus { id, .. } | AdminAction::ResumeAsset { id } => {
+            apply_in_place(&mut StateAccount::load_mut(accounts.state)?, action)?;
+            // Best effort: the status change must not depend on the mirror being passed
+            match accounts.remaining.first() {
+                Some(mirror) => sync_mirror_status(accounts.state, std::slice::from_ref(mirror), &[id]),
+                None => Ok(()),
+            }
+        }
+        action => apply_in_place(&mut StateAccount::load_mut(accounts.state)?, action),
+    }
+}
+
+/// Actions that only rewrite the state account's existing data
+fn apply_in_place<D: DerefMut<Target = [u8]>>(state: &mut StateAccount<D>, action: AdminAction) -> Result<()> {
+    match action {
+        AdminAction::SetAggregationConfig { min_quorum, stale_after_secs } => {
+            require!((1..=4).contains(&min_quorum), OracleError::BadQuorum);
+            require!(stale_after_secs > 0, OracleError::BadStaleness);
+            let s = state.header_mut();
+            s.min_quorum = min_quorum;
+            s.stale_after_secs = stale_after_secs;
+        }
+        AdminAction::SetMaxClockDrift { max_clock_drift_ms } => {
+            require!(max_clock_drift_ms > 0, OracleError::BadClockDrift);
+            state.header_mut().max_clock_drift_ms = max_clock_drift_ms;
+        }
+        AdminAction::SetEmaPeriod { ema_period_secs } => {
+            require!(ema_period_secs > 0, OracleError::BadEmaPeriod);
+            state.header_mut().ema_period_secs = ema_period_secs;
+        }
+        AdminAction::AddUpdater { index, updater } => {
+            let s = state.header_mut();
+            require_keys_eq!(s.updater(index)?, Pubkey::default(), OracleError::UpdaterSlotOccupied);
+            require_keys_neq!(updater, Pubkey::default(), OracleError::BadKey);
+            replace_updater(s, index, updater)?;
+        }
+        AdminAction::RemoveUpdater { index } => {
+            let s = state.header_mut();
+            require_keys_neq!(s.updater(index)?, Pubkey::default(), OracleError::UpdaterSlotEmpty);
+            replace_updater(s, index, Pubkey::default())?;
+            clear_updater_slots(state, index);
+        }
+        AdminAction::RotateUpdater { index, new_updater } => {
+            let s = state.header_mut();
+            require_keys_neq!(s.updater(index)?, Pubkey::default(), OracleError::UpdaterSlotEmpty);
+            require_keys_neq!(new_updater, Pubkey::default(), OracleError::BadKey);
+            replace_updater(s, index, new_updater)?;
+            clear_updater_slots(state, index);
+        }
+        AdminAction::SetAssetDecimals { id, decimals } => {
+            require!(decimals <= MAX_DECIMALS, OracleError::BadDecimals);
+            let entry = state.asset_mut(id)?;
+            // Recorded history stays in the old decimals, so it has to go first
+            require!(entry.has_history == 0, OracleError::HistoryEnabled);
+            let old_decimals = entry.decimals;
+            entry.prices.rescale(old_decimals, decimals)?;
+            entry.round.rescale(old_decimals, decimals)?;
+            entry.breaker.reset_window();
+            entry.decimals = decimals;
+            emit!(AssetDecimalsChanged {
+                id,
+                old_decimals,
+                new_decimals: decimals,
+            });
+        }
+        AdminAction::SetCircuitBreaker { id, max_move_bps, max_window_move_bps, window_secs, halt_on_breach } => {
+            require!(max_window_move_bps == 0 || window_secs > 0, OracleError::BadBreakerConfig);
+            let breaker = &mut state.asset_mut(id)?.breaker;
+            breaker.max_move_bps = max_move_bps;
+            breaker.max_window_move_bps = max_window_move_bps;
+            breaker.window_secs = window_secs;
+            breaker.halt_on_breach = halt_on_breach as u8;
+            breaker.reset_window();
+        }
+        AdminAction::SetOutlierThreshold { id, outlier_bps } => {
+            state.asset_mut(id)?.outlier_bps = outlier_bps;
+        }
+        AdminAction::SetRoundConfig { id, min_answers, tolerance_bps } => {
+            require!(min_answers <= 4, OracleError::BadRoundConfig);
+            let round = &mut state.asset_mut(id)?.round;
+            round.min_answers = min_answers;
+            round.tolerance_bps = tolerance_bps;
+        }
+        AdminAction::ResumeAsset { id } => {
+            let entry = state.asset_mut(id)?;
+            require!(entry.is_halted(), OracleError::AssetNotHalted);
+            entry.status = AssetStatus::Active as u8;
+            entry.breaker.reset_window();
+            emit!(AssetResumed { id });
+        }
+        AdminAction::SetAssetStatus { id, status } => {
+            let entry = state.asset_mut(id)?;
+            let old_status = entry.status();
+            if old_status == AssetStatus::Halted {
+                entry.breaker.reset_window();
+            }
+            entry.status = status as u8;
+            emit!(AssetStatusChanged {
+                id,
+                old_status,
+                new_status: status,
+            });
+        }
+        AdminAction::SetPaused { paused } => {
+            state.header_mut().paused = paused as u8;
+            emit!(PausedChanged { paused });
+        }
+        AdminAction::ProposeAuthority { new_authority } => {
+            require_keys_neq!(new_authority, Pubkey::default(), OracleError::BadKey);
+            let s = state.header_mut();
+            s.pending_authority = new_authority;
+            emit!(AuthorityProposed {
+                authority: s.update_authority,
+                pending_authority: new_authority,
+            });
+        }
+        AdminAction::CancelAuthorityTransfer => {
+            let s = state.header_mut();
+            require_keys_neq!(s.pending_authority, Pubkey::default(), OracleError::NoPendingAuthority);
+            let pending_authority = s.pending_authority;
+            s.pending_authority = Pubkey::default();
+            emit!(AuthorityTransferCancelled {
+                authority: s.update_authority,
+                pending_authority,
+            });
+        }
+        AdminAction::SetTimelock { timelock_secs } => {
+            require!(timelock_secs <= MAX_TIMELOCK_SECS, OracleError::BadTimelock);
+            let s = state.header_mut();
+            let old_timelock_secs = s.timelock_secs;
+            s.timelock_secs = timelock_secs;
+            emit!(TimelockChanged {
+                old_timelock_secs,
+                new_timelock_secs: timelock_secs,
+            });
+        }
+        AdminAction::AddAsset { .. }
+        | AdminAction::RetireAsset { .. }
+        | AdminAction::InitHistory { .. }
+        | AdminAction::CloseHistory { .. }
+        | AdminAction::InitGovernance { .. }
+        | AdminAction::InitPythMirror { .. }
+        | AdminAction::ClosePythMirror { .. }
+        | AdminAction::CloseState
+        | AdminAction::SetGovernance { .. } => unreachable!(),
+    }
+    Ok(())
+}
+
+fn replace_updater(s: &mut State, index: u8, new_updater: Pubkey) -> Result<()> {
+    let old_updater = s.set_updater(index, new_updater)?;
+    emit!(UpdaterChanged {
+        index,
+        old_updater,
+        new_updater,
+    });
+    Ok(())
+}
+
+/// Drop what the previous key of slot `index` wrote to every asset, so a
+/// removed or compromised relay stops counting toward any aggregate or round.
+/// Aggregates it contributed to are dropped too; see `Triplet::clear`.
+fn clear_updater_slots<D: DerefMut<Target = [u8]>>(state: &mut StateAccount<D>, index: u8) {
+    let stale_after_secs = state.header().stale_after_secs;
+    for entry in state.assets_mut() {
+        entry.prices.clear(index, stale_after_secs);
+        entry.round.withdraw(index);
+    }
+}
+
+fn add_asset_entry<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    id: u8,
+    symbol: String,
+    decimals: u8,
+) -> Result<()> {
+    let entry = AssetEntry::new(id, &symbol, decimals)?;
+    let asset_count = {
+        let state = StateAccount::load(info)?;
+        require!(state.asset(id).is_err(), OracleError::AssetAlreadyRegistered);
+        require!(
+            !state.assets().iter().any(|a| a.symbol == entry.symbol),
+            OracleError::DuplicateSymbol
+        );
+        state.header().asset_count as usize
+    };
+
+    resize_state(info, payer, system_program, State::space(asset_count + 1))?;
+
+    let mut state = StateAccount::load_mut(info)?;
+    state.header_mut().asset_count += 1;
+    state.assets_mut()[asset_count] = entry;
+
+    emit!(AssetAdded { id, symbol, decimals });
+    Ok(())
+}
+
+fn retire_asset_entry<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    id: u8,
+) -> Result<()> {
+    let (entry, asset_count) = {
+        let mut state = StateAccount::load_mut(info)?;
+        let entry = state.remove_asset(id)?;
+        (entry, state.header().asset_count as usize)
+    };
+
+    resize_state(info, payer, system_program, State::space(asset_count))?;
+
+    emit!(AssetRetired {
+        id,
+        symbol: entry.symbol_as_string(),
+    });
+    Ok(())
+}
+
+/// Resize the state account to `new_len`, topping up rent from `payer` or
+/// refunding the excess to it.
+fn resize_state<'info>(
+    state: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(new_len);
+    let lamports = state.lamports();
+    if rent > lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: state.clone(),
+                },
+            ),
+            rent - lamports,
+        )?;
+    } else if lamports > rent {
+        **state.try_borrow_mut_lamports()? -= lamports - rent;
+        **payer.try_borrow_mut_lamports()? += lamports - rent;
+    }
+    state.resize(new_len)?;
+    Ok(())
+}
+
+/// Create `account` as a program-owned PDA of `space` bytes, rent paid by
+/// `payer`. Works on an address someone has already sent lamports to.
+pub(crate) fn create_pda<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    let signer = &[seeds];
+    if lamports == 0 {
+        let accounts = CreateAccount {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        let ctx = CpiContext::new_with_signer(system_program.clone(), accounts, signer);
+        return system_program::create_account(ctx, rent, space as u64, &crate::ID);
+    }
+
+    if rent > lamports {
+        let accounts = Transfer {
+            from: payer.clone(),
+            to: account.clone(),
+        };
+        system_program::transfer(CpiContext::new(system_program.clone(), accounts), rent - lamports)?;
+    }
+    let accounts = Allocate {
+        account_to_allocate: account.clone(),
+    };
+    system_program::allocate(CpiContext::new_with_signer(system_program.clone(), accounts, signer), space as u64)?;
+    let accounts = Assign {
+        account_to_assign: account.clone(),
+    };
+    system_program::assign(CpiContext::new_with_signer(system_program.clone(), accounts, signer), &crate::ID)
+}
+
+/// Close a program-owned account into `recipient`.
+pub(crate) fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> Result<()> {
+    // Wipe the data before handing the account back to the system program so
+    // nothing can be read or revived from it later in the same transaction.
+    account.try_borrow_mut_data()?.fill(0);
+    let lamports = account.lamports();
+    **account.lamports.borrow_mut() = 0;
+    **recipient.lamports.borrow_mut() += lamports;
+    account.assign(&System::id());
+    account.resize(0)?;
+    Ok(())
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct UpdaterChanged {
+    pub index: u8,           // 1,2,3,4
+    pub old_updater: Pubkey, // Pubkey::default() when the slot was empty
+    pub new_updater: Pubkey, // Pubkey::default() when the slot was cleared
+}
+
+#[event]
+pub struct AssetAdded {
+    pub id: u8,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[event]
+pub struct AssetDecimalsChanged {
+    pub id: u8,
+    pub old_decimals: u8,
+    pub new_decimals: u8,
+}
+
+#[event]
+pub struct AssetResumed {
+    pub id: u8,
+}
+
+#[event]
+pub struct AssetStatusChanged {
+    pub id: u8,
+    pub old_status: AssetStatus,
+    pub new_status: AssetStatus,
+}
+
+#[event]
+pub struct PausedChanged {
+    pub paused: bool,
+}
+
+#[event]
+pub struct TimelockChanged {
+    pub old_timelock_secs: u32,
+    pub new_timelock_secs: u32,
+}
+
+#[event]
+pub struct AssetRetired {
+    pub id: u8,
+    pub symbol: String,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = State::space(Asset::ALL.len()),
+        seeds = [State::SEED],
+        bump
+    )]
+    pub state: AccountLoader<'info, State>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Oracle>,
+    /// Only the upgrade authority may initialize, so nobody can race a fresh
+    /// deploy (or a `close_state`) and seize the admin role
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ OracleError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub upgrade_authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetUpdateAuthority<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    pub signer: Signer<'info>,
+}
+
+impl<'info> SetUpdateAuthority<'info> {
+    /// Apply an action that only touches the state account, as the admin
+    fn run(&self, action: AdminAction) -> Result<()> {
+        self.run_with(action, &[])
+    }
+
+    /// Apply an action that also updates `remaining` (Pyth mirrors), as the admin
+    fn run_with(&self, action: AdminAction, remaining: &[AccountInfo<'info>]) -> Result<()> {
+        let state = self.state.to_account_info();
+        require_admin(&state, &self.signer, &action)?;
+        let accounts = ActionAccounts {
+            state: &state,
+            payer: None,
+            system_program: None,
+            remaining,
+        };
+        apply_action(&accounts, action)
+    }
+}
+
+#[derive(Accounts)]
+pub struct AddAsset<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RetireAsset<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseState<'info> {
+    /// CHECK: We use AccountInfo instead of Account to avoid deserialization
+    /// This allows closing accounts with old structure; the admin is checked in the handler
+    #[account(
+        mut,
+        seeds = [State::SEED],
+        bump,
+        owner = crate::ID
+    )]
+    pub state: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: Receives the lamports from the closed account
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
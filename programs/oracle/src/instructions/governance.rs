@@ -0,0 +1,324 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use crate::state::*;
+use super::admin::{apply_action, create_pda, require_admin, ActionAccounts};
+
+/// Create the governance account and hand it the state's admin role. From
+/// then on admin actions need `threshold` of `signers` to approve a proposal.
+/// Timelocked like any other authority change. A governance account left
+/// behind by `close_state` is taken over with a new epoch.
+pub fn init_governance(ctx: Context<InitGovernance>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let governance = a.governance.to_account_info();
+    let action = AdminAction::InitGovernance { signers, threshold };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.payer),
+        system_program: Some(&a.system_program),
+        remaining: std::slice::from_ref(&governance),
+    };
+    apply_action(&accounts, action)
+}
+
+/// Create the `Governance` PDA at `governance` and make it the admin.
+pub(crate) fn create_governance<'info>(
+    state: &AccountInfo<'info>,
+    governance: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    Governance::validate(&signers, threshold)?;
+    let (address, bump) = Pubkey::find_program_address(&[Governance::SEED], &crate::ID);
+    require_keys_eq!(governance.key(), address, OracleError::MissingActionAccount);
+    {
+        let state = StateAccount::load(state)?;
+        require_keys_neq!(state.header().update_authority, address, OracleError::GovernanceActive);
+    }
+
+    // A governance account outlives a `close_state`, so take it over instead
+    // of creating it: the epoch moves on so its old proposals lapse, and ids
+    // keep counting so new proposals do not collide with the leftovers.
+    let (epoch, proposal_count) = if *governance.owner == crate::ID {
+        let old = Governance::try_deserialize(&mut &governance.try_borrow_data()?[..])?;
+        (old.epoch + 1, old.proposal_count)
+    } else {
+        create_pda(governance, payer, system_program, &[Governance::SEED, &[bump]], 8 + Governance::SIZE)?;
+        (0, 0)
+    };
+
+    let g = Governance {
+        signers: signers.clone(),
+        threshold,
+        epoch,
+        proposal_count,
+        bump,
+    };
+    let mut data = governance.try_borrow_mut_data()?;
+    g.try_serialize(&mut &mut data[..])?;
+
+    let mut state = StateAccount::load_mut(state)?;
+    let s = state.header_mut();
+    s.update_authority = address;
+    s.pending_authority = Pubkey::default();
+
+    emit!(GovernanceChanged { signers, threshold, epoch });
+    Ok(())
+}
+
+/// Open a proposal for `action`; counts as the proposer's approval
+pub fn create_proposal(ctx: Context<CreateProposal>, action: AdminAction) -> Result<()> {
+    let g = &mut ctx.accounts.governance;
+    let index = g.signer_index(&ctx.accounts.proposer.key())?;
+    if let AdminAction::SetGovernance { signers, threshold } = &action {
+        Governance::validate(signers, *threshold)?;
+    }
+
+    let p = &mut ctx.accounts.proposal;
+    p.id = g.proposal_count;
+    p.proposer = ctx.accounts.proposer.key();
+    p.epoch = g.epoch;
+    p.approvals = 1 << index;
+    p.vetoes = 0;
+    p.bump = ctx.bumps.proposal;
+    p.eta = 0;
+    p.action = action.clone();
+    g.proposal_count += 1;
+
+    emit!(ProposalCreated {
+        id: p.id,
+        proposer: p.proposer,
+        action,
+    });
+    start_timelock(p, g, &*ctx.accounts.state.load()?)
+}
+
+pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+    let g = &ctx.accounts.governance;
+    let p = &mut ctx.accounts.proposal;
+    p.approve(g, &ctx.accounts.signer.key())?;
+
+    emit!(ProposalApproved {
+        id: p.id,
+        signer: ctx.accounts.signer.key(),
+        approvals: p.approval_count() as u8,
+    });
+    start_timelock(p, g, &*ctx.accounts.state.load()?)
+}
+
+/// Set the ETA once a proposal first reaches its threshold
+fn start_timelock(p: &mut Proposal, g: &Governance, s: &State) -> Result<()> {
+    if p.eta == 0 && p.approval_count() >= g.threshold as u32 {
+        p.eta = Clock::get()?.unix_timestamp + s.timelock_secs as i64;
+        emit!(ProposalQueued { id: p.id, eta: p.eta });
+    }
+    Ok(())
+}
+
+/// Carry out an approved proposal and close it. Any governance signer may
+/// execute; they pay for state growth and receive any refunded rent.
+/// Accounts the action creates or closes go in `remaining_accounts`.
+pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+    let a = &ctx.accounts;
+    let g = &a.governance;
+    let p = &a.proposal;
+    g.signer_index(&a.executor.key())?;
+    require!(p.epoch == g.epoch, OracleError::StaleProposal);
+    require!(p.approval_count() >= g.threshold as u32, OracleError::ThresholdNotMet);
+    if p.action.is_timelocked() {
+        require!(Clock::get()?.unix_timestamp >= p.eta, OracleError::TimelockNotElapsed);
+    }
+    {
+        let state = StateAccount::load(&a.state)?;
+        require_keys_eq!(state.header().update_authority, g.key(), OracleError::NotGovernanceAuthority);
+    }
+
+    let id = p.id;
+    let action = p.action.clone();
+    if let AdminAction::SetGovernance { signers, threshold } = action {
+        let g = &mut ctx.accounts.governance;
+        g.set_signers(signers.clone(), threshold);
+        emit!(GovernanceChanged { signers, threshold, epoch: g.epoch });
+    } else {
+        let accounts = ActionAccounts {
+            state: &a.state,
+            payer: Some(&a.executor),
+            system_program: Some(&a.system_program),
+            remaining: ctx.remaining_accounts,
+        };
+        apply_action(&accounts, action)?;
+    }
+
+    emit!(ProposalExecuted { id });
+    Ok(())
+}
+
+/// Withdraw a proposal; only its proposer can
+pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    emit!(ProposalCancelled {
+        id: ctx.accounts.proposal.id,
+        cancelled_by: ctx.accounts.proposer.key(),
+    });
+    Ok(())
+}
+
+/// Veto a timelocked proposal before its ETA. Once `veto_threshold` signers
+/// have vetoed, the others can no longer approve it, so it is cancelled and
+/// closed to its proposer; a single signer cannot block governance alone.
+pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+    let a = ctx.accounts;
+    let signer = a.signer.key();
+    let cancelled = a.proposal.veto(&a.governance, &signer, Clock::get()?.unix_timestamp)?;
+
+    let id = a.proposal.id;
+    emit!(ProposalVetoed {
+        id,
+        signer,
+        vetoes: a.proposal.veto_count() as u8,
+    });
+    if cancelled {
+        emit!(ProposalCancelled { id, cancelled_by: signer });
+        a.proposal.close(a.proposer.to_account_info())?;
+    }
+    Ok(())
+}
+
+#[event]
+pub struct GovernanceChanged {
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub epoch: u32,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub action: AdminAction,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub id: u64,
+    pub signer: Pubkey,
+    pub approvals: u8, // approvals so far, this one included
+}
+
+#[event]
+pub struct ProposalQueued {
+    pub id: u64,
+    pub eta: i64, // earliest execution if the action is timelocked
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub id: u64,
+}
+
+#[event]
+pub struct ProposalVetoed {
+    pub id: u64,
+    pub signer: Pubkey,
+    pub vetoes: u8, // vetoes so far, this one included
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub id: u64,
+    pub cancelled_by: Pubkey, // the proposer, or the signer casting the deciding veto
+}
+
+#[derive(Accounts)]
+pub struct InitGovernance<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    /// CHECK: Created by the handler at the `[b"governance"]` PDA
+    #[account(mut, seeds = [Governance::SEED], bump)]
+    pub governance: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    #[account(mut, seeds = [Governance::SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::SIZE,
+        seeds = [Proposal::SEED, &governance.proposal_count.to_le_bytes()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    #[account(seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    #[account(seeds = [Governance::SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, seeds = [Proposal::SEED, &proposal.id.to_le_bytes()], bump = proposal.bump)]
+    pub proposal: Account<'info, Proposal>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// CHECK: Raw so that `CloseState` can hand it back to the system program;
+    /// the handler loads it through `StateAccount`
+    #[account(mut, seeds = [State::SEED], bump, owner = crate::ID)]
+    pub state: UncheckedAccount<'info>,
+    #[account(mut, seeds = [Governance::SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [Proposal::SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Receives the proposal's rent
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(
+        mut,
+        close = proposer,
+        has_one = proposer,
+        seeds = [Proposal::SEED, &proposal.id.to_le_bytes()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VetoProposal<'info> {
+    #[account(seeds = [Governance::SEED], bump = governance.bump)]
+    pub governance: Account<'info, Governance>,
+    #[account(mut, seeds = [Proposal::SEED, &proposal.id.to_le_bytes()], bump = proposal.bump)]
+    pub proposal: Account<'info, Proposal>,
+    /// CHECK: Receives the proposal's rent if the veto cancels it
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+}
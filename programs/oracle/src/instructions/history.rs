@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use crate::state::*;
+use super::admin::{apply_action, close_account, create_pda, require_admin, ActionAccounts};
+
+/// Attach a `PriceHistory` ring buffer to an asset. From then on every
+/// update of the asset must pass the account in `remaining_accounts`.
+pub fn init_history(ctx: Context<InitHistory>, asset: u8) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let history = a.history.to_account_info();
+    let action = AdminAction::InitHistory { asset };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.payer),
+        system_program: Some(&a.system_program),
+        remaining: std::slice::from_ref(&history),
+    };
+    apply_action(&accounts, action)
+}
+
+/// Detach and close an asset's `PriceHistory`, refunding its rent to `recipient`
+pub fn close_history(ctx: Context<CloseHistory>, asset: u8) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let history = a.history.to_account_info();
+    let action = AdminAction::CloseHistory { asset };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.recipient),
+        system_program: None,
+        remaining: std::slice::from_ref(&history),
+    };
+    apply_action(&accounts, action)
+}
+
+/// Create the `PriceHistory` PDA of `asset` at `history` and flag the asset.
+pub(crate) fn attach_history<'info>(
+    state: &AccountInfo<'info>,
+    history: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    asset: u8,
+) -> Result<()> {
+    {
+        let mut state = StateAccount::load_mut(state)?;
+        let entry = state.asset_mut(asset)?;
+        require!(entry.has_history == 0, OracleError::HistoryEnabled);
+        entry.has_history = 1;
+    }
+
+    let (address, bump) = Pubkey::find_program_address(&[PriceHistory::SEED, &[asset]], &crate::ID);
+    require_keys_eq!(history.key(), address, OracleError::BadHistoryAccount);
+    create_pda(
+        history,
+        payer,
+        system_program,
+        &[PriceHistory::SEED, &[asset], &[bump]],
+        8 + PriceHistory::SIZE,
+    )?;
+
+    let mut data = history.try_borrow_mut_data()?;
+    data[..8].copy_from_slice(PriceHistory::DISCRIMINATOR);
+    let h: &mut PriceHistory = bytemuck::from_bytes_mut(&mut data[8..8 + PriceHistory::SIZE]);
+    h.asset = asset;
+    h.bump = bump;
+    Ok(())
+}
+
+/// Close the `PriceHistory` of `asset` into `recipient` and clear the flag.
+pub(crate) fn detach_history(state: &AccountInfo, history: &AccountInfo, recipient: &AccountInfo, asset: u8) -> Result<()> {
+    {
+        let mut state = StateAccount::load_mut(state)?;
+        let entry = state.asset_mut(asset)?;
+        require!(entry.has_history != 0, OracleError::HistoryDisabled);
+        entry.has_history = 0;
+    }
+
+    require_keys_eq!(*history.owner, crate::ID, OracleError::BadHistoryAccount);
+    {
+        let data = history.try_borrow_data()?;
+        require!(data.len() >= 8 + PriceHistory::SIZE, OracleError::BadHistoryAccount);
+        require!(data[..8] == *PriceHistory::DISCRIMINATOR, OracleError::BadHistoryAccount);
+        let h: &PriceHistory = bytemuck::from_bytes(&data[8..8 + PriceHistory::SIZE]);
+        require!(h.asset == asset, OracleError::BadHistoryAccount);
+    }
+    close_account(history, recipient)
+}
+
+#[derive(Accounts)]
+#[instruction(asset: u8)]
+pub struct InitHistory<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    /// CHECK: Created by the handler at the `[b"history", &[asset]]` PDA
+    #[account(mut, seeds = [PriceHistory::SEED, &[asset]], bump)]
+    pub history: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset: u8)]
+pub struct CloseHistory<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    /// CHECK: Owner, discriminator and asset are checked by the handler
+    #[account(mut, seeds = [PriceHistory::SEED, &[asset]], bump)]
+    pub history: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    /// CHECK: Receives the lamports from the closed account
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
@@ -0,0 +1,17 @@
+pub mod admin;
+pub mod governance;
+pub mod history;
+pub mod pyth_mirror;
+pub mod read_price;
+pub mod set_price;
+pub mod signed_price;
+pub mod timelock;
+
+pub use admin::*;
+pub use governance::*;
+pub use history::*;
+pub use pyth_mirror::*;
+pub use read_price::*;
+pub use set_price::*;
+pub use signed_price::*;
+pub use timelock::*;
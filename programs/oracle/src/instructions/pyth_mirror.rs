@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use crate::state::*;
+use super::admin::{apply_action, close_account, create_pda, require_admin, ActionAccounts};
+
+/// Attach a Pyth-layout mirror to an asset, filled from the current aggregate
+/// if there is one. From then on every update of the asset must pass the
+/// account in `remaining_accounts`.
+pub fn init_pyth_mirror(ctx: Context<InitPythMirror>, asset: u8, feed_id: [u8; 32]) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let mirror = a.mirror.to_account_info();
+    let action = AdminAction::InitPythMirror { asset, feed_id };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.payer),
+        system_program: Some(&a.system_program),
+        remaining: std::slice::from_ref(&mirror),
+    };
+    apply_action(&accounts, action)
+}
+
+/// Detach and close an asset's Pyth-layout mirror, refunding its rent to `recipient`
+pub fn close_pyth_mirror(ctx: Context<ClosePythMirror>, asset: u8) -> Result<()> {
+    let a = &ctx.accounts;
+    let state = a.state.to_account_info();
+    let mirror = a.mirror.to_account_info();
+    let action = AdminAction::ClosePythMirror { asset };
+    require_admin(&state, &a.signer, &action)?;
+    let accounts = ActionAccounts {
+        state: &state,
+        payer: Some(&a.recipient),
+        system_program: None,
+        remaining: std::slice::from_ref(&mirror),
+    };
+    apply_action(&accounts, action)
+}
+
+/// Check that `mirror` is the mirror PDA attached to `entry`.
+pub(crate) fn check_mirror(mirror: &AccountInfo, entry: &AssetEntry) -> Result<()> {
+    let expected = Pubkey::create_program_address(
+        &[PriceUpdateV2::SEED, &[entry.id], &[entry.pyth_mirror_bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(OracleError::BadPythMirror))?;
+    require_keys_eq!(mirror.key(), expected, OracleError::BadPythMirror);
+    require_keys_eq!(*mirror.owner, crate::ID, OracleError::BadPythMirror);
+    Ok(())
+}
+
+/// Bring the verification level of the mirrors of `assets` (one per entry of
+/// `mirrors`, same order) in line with whether the asset is served. Anyone
+/// may, so a pause over many mirrors can be propagated in chunks.
+pub fn sync_pyth_mirrors<'info>(ctx: Context<'_, '_, '_, 'info, SyncPythMirrors<'info>>, assets: Vec<u8>) -> Result<()> {
+    sync_mirror_status(ctx.accounts.state.as_ref(), ctx.remaining_accounts, &assets)
+}
+
+/// Set the verification level of each of `mirrors`, the mirror of the asset
+/// at the same position in `assets`.
+pub(crate) fn sync_mirror_status(state: &AccountInfo, mirrors: &[AccountInfo], assets: &[u8]) -> Result<()> {
+    require!(mirrors.len() >= assets.len(), OracleError::MissingPythMirror);
+    let state = StateAccount::load(state)?;
+    let paused = state.header().paused != 0;
+    for (info, &asset) in mirrors.iter().zip(assets) {
+        let entry = state.asset(asset)?;
+        require!(entry.has_pyth_mirror != 0, OracleError::PythMirrorDisabled);
+        check_mirror(info, entry)?;
+        let mut data = info.try_borrow_mut_data()?;
+        let mut m = PriceUpdateV2::try_deserialize(&mut &data[..])?;
+        m.verification_level = if paused || entry.is_halted() {
+            PriceUpdateV2::UNSERVABLE
+        } else {
+            VerificationLevel::Full
+        };
+        m.try_serialize(&mut &mut data[..])?;
+    }
+    Ok(())
+}
+
+/// Create the `PriceUpdateV2` mirror PDA of `asset` at `mirror` and flag the asset.
+pub(crate) fn attach_pyth_mirror<'info>(
+    state: &AccountInfo<'info>,
+    mirror: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    asset: u8,
+    feed_id: [u8; 32],
+) -> Result<()> {
+    let (address, bump) = Pubkey::find_program_address(&[PriceUpdateV2::SEED, &[asset]], &crate::ID);
+    require_keys_eq!(mirror.key(), address, OracleError::BadPythMirror);
+
+    let mut m = PriceUpdateV2 {
+        write_authority: state.key(),
+        verification_level: VerificationLevel::Full,
+        price_message: PriceFeedMessage {
+            feed_id,
+            ..Default::default()
+        },
+        posted_slot: 0,
+    };
+    {
+        let mut state = StateAccount::load_mut(state)?;
+        let paused = state.header().paused != 0;
+        let entry = state.asset_mut(asset)?;
+        require!(entry.has_pyth_mirror == 0, OracleError::PythMirrorEnabled);
+        entry.has_pyth_mirror = 1;
+        entry.pyth_mirror_bump = bump;
+        m.price_message.exponent = -(entry.decimals as i32);
+        if entry.prices.agg_count > 0 {
+            m.mirror(&entry.prices, entry.decimals, Clock::get()?.slot);
+        }
+        if paused || entry.is_halted() {
+            m.verification_level = PriceUpdateV2::UNSERVABLE;
+        }
+    }
+
+    create_pda(
+        mirror,
+        payer,
+        system_program,
+        &[PriceUpdateV2::SEED, &[asset], &[bump]],
+        8 + PriceUpdateV2::SIZE,
+    )?;
+    let mut data = mirror.try_borrow_mut_data()?;
+    m.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+/// Close the mirror of `asset` into `recipient` and clear the flag.
+pub(crate) fn detach_pyth_mirror(state: &AccountInfo, mirror: &AccountInfo, recipient: &AccountInfo, asset: u8) -> Result<()> {
+    let mut state = StateAccount::load_mut(state)?;
+    let entry = state.asset_mut(asset)?;
+    require!(entry.has_pyth_mirror != 0, OracleError::PythMirrorDisabled);
+    check_mirror(mirror, entry)?;
+    entry.has_pyth_mirror = 0;
+    entry.pyth_mirror_bump = 0;
+    close_account(mirror, recipient)
+}
+
+#[derive(Accounts)]
+#[instruction(asset: u8)]
+pub struct InitPythMirror<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    /// CHECK: Created by the handler at the `[b"pyth_mirror", &[asset]]` PDA
+    #[account(mut, seeds = [PriceUpdateV2::SEED, &[asset]], bump)]
+    pub mirror: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncPythMirrors<'info> {
+    #[account(seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+}
+
+#[derive(Accounts)]
+#[instruction(asset: u8)]
+pub struct ClosePythMirror<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    /// CHECK: Address and owner are checked by the handler
+    #[account(mut, seeds = [PriceUpdateV2::SEED, &[asset]], bump)]
+    pub mirror: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    /// CHECK: Receives the lamports from the closed account
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::consumer::{self, Price, RoundData};
+use crate::errors::OracleError;
+use crate::state::*;
+
+/// Upper bound for `get_prices` so the result fits in the 1024-byte return data
+pub const MAX_PRICES_PER_QUERY: usize = 32;
+
+/// Validated price of one asset, returned through return data
+pub fn get_price(ctx: Context<ReadPrice>, asset: u8, max_age_secs: u64) -> Result<Price> {
+    consumer::get_price(ctx.accounts.state.as_ref(), asset, max_age_secs)
+}
+
+/// Validated prices of several assets, in request order, returned through return data
+pub fn get_prices(ctx: Context<ReadPrice>, assets: Vec<u8>, max_age_secs: u64) -> Result<Vec<Price>> {
+    require!(assets.len() <= MAX_PRICES_PER_QUERY, OracleError::TooManyAssets);
+    assets
+        .into_iter()
+        .map(|asset| consumer::get_price(ctx.accounts.state.as_ref(), asset, max_age_secs))
+        .collect()
+}
+
+/// Latest round of one asset, returned through return data
+pub fn latest_round_data(ctx: Context<ReadPrice>, asset: u8) -> Result<RoundData> {
+    consumer::latest_round_data(ctx.accounts.state.as_ref(), asset)
+}
+
+#[derive(Accounts)]
+pub struct ReadPrice<'info> {
+    #[account(seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+}
@@ -0,0 +1,306 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use crate::state::*;
+use super::pyth_mirror::check_mirror;
+
+/// Write one updater slot of a single asset
+pub fn set_price<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetPrice<'info>>,
+    asset: u8,
+    index: u8,
+    price: i64,
+    client_ts_ms: i64,
+    seq: Option<u64>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut state = StateAccount::load_mut(ctx.accounts.state.as_ref())?;
+    let s = *state.header();
+    require_keys_eq!(ctx.accounts.signer.key(), s.updater(index)?, OracleError::UnauthorizedForIndex);
+    require!(s.paused == 0, OracleError::OraclePaused);
+    s.check_client_ts(client_ts_ms, &clock)?;
+    state.header_mut().advance_seq(index, seq)?;
+
+    let entry = state.asset_mut(asset)?;
+    require!(!entry.is_halted(), OracleError::AssetHalted);
+    let mut remaining = ctx.remaining_accounts.iter();
+    write_slot(&s, entry, &mut remaining, index, price, client_ts_ms, &clock, false)
+}
+
+/// Write one updater slot of each listed asset; halted assets and writes
+/// rejected by the circuit breaker are skipped
+pub fn batch_set_prices<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SetPrice<'info>>,
+    index: u8,
+    updates: Vec<PriceUpdate>,
+    client_ts_ms: i64,
+    seq: Option<u64>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut state = StateAccount::load_mut(ctx.accounts.state.as_ref())?;
+    let s = *state.header();
+    require_keys_eq!(ctx.accounts.signer.key(), s.updater(index)?, OracleError::UnauthorizedForIndex);
+    require!(s.paused == 0, OracleError::OraclePaused);
+    s.check_client_ts(client_ts_ms, &clock)?;
+    state.header_mut().advance_seq(index, seq)?;
+    require!(!updates.is_empty(), OracleError::EmptyBatch);
+
+    // Unknown ids fail inside write_slot; any other error reverts the whole batch
+    let mut seen = [false; 256];
+    let mut remaining = ctx.remaining_accounts.iter();
+    for update in updates {
+        require!(!seen[update.asset as usize], OracleError::DuplicateAsset);
+        seen[update.asset as usize] = true;
+        let entry = state.asset_mut(update.asset)?;
+        write_slot(&s, entry, &mut remaining, index, update.price, client_ts_ms, &clock, true)?;
+    }
+
+    Ok(())
+}
+
+/// Store `price` in slot `index` of `entry`, re-aggregate, and emit the events.
+///
+/// Assets with history enabled take the next account from `remaining` (the
+/// instruction's `remaining_accounts`, one per such asset in update order)
+/// and append the new aggregate to it. Assets with a Pyth mirror then take
+/// the account after that and copy the new aggregate into it.
+///
+/// Writes whose `client_ts_ms` is not newer than the slot's current one are
+/// rejected with `StaleWrite`, so a delayed or replayed transaction cannot
+/// roll the price back.
+///
+/// Slots too far from the median of the others are left out of the aggregate
+/// and reported with an `OutlierExcluded` event each time it is recomputed.
+///
+/// The price is also submitted to the asset's open round when rounds are on.
+///
+/// A write that would move the aggregate past the asset's circuit breaker is
+/// rejected, or halts the asset without being written when the breaker is set
+/// to halt. With `skip_rejected` a rejected write is dropped with a
+/// `PriceRejected` event instead of failing, so one asset cannot revert a
+/// batch.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_slot<'info>(
+    s: &State,
+    entry: &mut AssetEntry,
+    remaining: &mut std::slice::Iter<'info, AccountInfo<'info>>,
+    index: u8,
+    price: i64,
+    client_ts_ms: i64,
+    clock: &Clock,
+    skip_rejected: bool,
+) -> Result<()> {
+    require!(price > 0, OracleError::BadPrice);
+    let asset = entry.id;
+    let decimals = entry.decimals;
+
+    // Take the history and mirror accounts before anything can skip the
+    // update, so the remaining accounts stay lined up with the updates
+    let history = if entry.has_history != 0 {
+        let info = remaining.next().ok_or_else(|| error!(OracleError::MissingHistoryAccount))?;
+        let loader = AccountLoader::<PriceHistory>::try_from(info)?;
+        require!(loader.load()?.asset == asset, OracleError::BadHistoryAccount);
+        Some(loader)
+    } else {
+        None
+    };
+    let mirror = if entry.has_pyth_mirror != 0 {
+        let info = remaining.next().ok_or_else(|| error!(OracleError::MissingPythMirror))?;
+        check_mirror(info, entry)?;
+        Some(Account::<PriceUpdateV2>::try_from(info)?)
+    } else {
+        None
+    };
+
+    if entry.is_halted() {
+        return Ok(());
+    }
+    require!(client_ts_ms > entry.prices.client_ts(index), OracleError::StaleWrite);
+
+    // The breaker judges the aggregate this write would produce, after
+    // outliers are dropped, so one bad relay cannot trip it on its own
+    let now = clock.unix_timestamp;
+    let mut prices = entry.prices;
+    prices.write(index, price, client_ts_ms, clock);
+    let candidate = prices.candidate(now, s.stale_after_secs, s.min_quorum, entry.outlier_bps);
+    if let Some(aggregate) = &candidate {
+        let last = (entry.prices.agg_count > 0).then_some(entry.prices.agg_price);
+        if let Some(reference) = entry.breaker.check(aggregate.price, last, now) {
+            if entry.breaker.halt_on_breach == 0 {
+                require!(skip_rejected, OracleError::PriceMoveTooLarge);
+                emit!(PriceRejected {
+                    asset,
+                    index,
+                    price: aggregate.price,
+                    reference,
+                    slot: clock.slot,
+                });
+                return Ok(());
+            }
+            entry.status = AssetStatus::Halted as u8;
+            if let Some(mut mirror) = mirror {
+                mirror.verification_level = PriceUpdateV2::UNSERVABLE;
+                mirror.exit(&crate::ID)?;
+            }
+            emit!(AssetHalted {
+                asset,
+                index,
+                price: aggregate.price,
+                reference,
+                slot: clock.slot,
+            });
+            return Ok(());
+        }
+    }
+
+    let previous_round = entry.round.round_id;
+    let finalized = entry.round.submit(index, price, clock, s.stale_after_secs);
+    let round = entry.round;
+    entry.prices = prices;
+    let t = &mut entry.prices;
+    if let Some(aggregate) = &candidate {
+        t.commit(aggregate, now, s.ema_period_secs);
+    }
+    let aggregated = candidate.is_some();
+
+    if let (Some(history), true) = (history, aggregated) {
+        history.load_mut()?.push(HistoryEntry {
+            price: t.agg_price,
+            conf: t.agg_conf,
+            slot: clock.slot,
+            unix_ts: t.agg_ts,
+        });
+    }
+    if let (Some(mut mirror), true) = (mirror, aggregated) {
+        mirror.mirror(t, decimals, clock.slot);
+        mirror.exit(&crate::ID)?;
+    }
+
+    emit!(PriceUpdated {
+        asset,
+        index,
+        price,
+        decimals,
+        client_ts_ms,
+        slot: clock.slot,
+    });
+    if aggregated {
+        for (i, (outlier, _)) in t.slots().into_iter().enumerate() {
+            if t.excluded & (1 << i) != 0 {
+                emit!(OutlierExcluded {
+                    asset,
+                    index: i as u8 + 1,
+                    price: outlier,
+                    median: t.agg_price,
+                    slot: clock.slot,
+                });
+            }
+        }
+        emit!(PriceAggregated {
+            asset,
+            price: t.agg_price,
+            conf: t.agg_conf,
+            spread: t.agg_spread,
+            count: t.agg_count,
+            decimals,
+            slot: clock.slot,
+        });
+    }
+
+    if round.round_id != previous_round {
+        emit!(RoundStarted {
+            asset,
+            round_id: round.round_id,
+            slot: round.open_slot,
+        });
+    }
+    if finalized {
+        emit!(RoundFinalized {
+            asset,
+            round_id: round.round_id,
+            answer: round.answer,
+            decimals,
+            started_at: round.answer_started_at,
+            slot: round.close_slot,
+        });
+    }
+
+    Ok(())
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PriceUpdate {
+    pub asset: u8,
+    pub price: i64,
+}
+
+#[event]
+pub struct PriceUpdated {
+    pub asset: u8,        // asset id, see `Asset` for the defaults
+    pub index: u8,        // 1,2,3,4
+    pub price: i64,
+    pub decimals: u8,     // per-asset, see `AssetEntry::decimals`
+    pub client_ts_ms: i64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct PriceAggregated {
+    pub asset: u8,
+    pub price: i64,       // median of the fresh slots
+    pub conf: u64,        // median absolute deviation of the fresh slots
+    pub spread: i64,      // max - min of the fresh slots
+    pub count: u8,        // number of slots that contributed
+    pub decimals: u8,
+    pub slot: u64,
+}
+
+#[event]
+pub struct OutlierExcluded {
+    pub asset: u8,
+    pub index: u8,        // updater slot left out of the aggregate
+    pub price: i64,       // its price
+    pub median: i64,      // aggregate of the remaining slots
+    pub slot: u64,
+}
+
+#[event]
+pub struct RoundStarted {
+    pub asset: u8,
+    pub round_id: u64,
+    pub slot: u64,
+}
+
+#[event]
+pub struct RoundFinalized {
+    pub asset: u8,
+    pub round_id: u64,
+    pub answer: i64,      // median of the agreeing submissions
+    pub decimals: u8,
+    pub started_at: i64,  // cluster unix_timestamp the round opened
+    pub slot: u64,
+}
+
+#[event]
+pub struct AssetHalted {
+    pub asset: u8,
+    pub index: u8,        // updater slot whose write tripped the breaker
+    pub price: i64,       // rejected aggregate
+    pub reference: i64,   // price it was compared against
+    pub slot: u64,
+}
+
+#[event]
+pub struct PriceRejected {
+    pub asset: u8,
+    pub index: u8,        // updater slot whose write was dropped
+    pub price: i64,       // rejected aggregate
+    pub reference: i64,   // price it was compared against
+    pub slot: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetPrice<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    pub signer: Signer<'info>,
+}
@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{self as instructions_sysvar, get_instruction_relative};
+use crate::errors::OracleError;
+use crate::state::*;
+use super::set_price::write_slot;
+
+/// Price observation signed off-chain by an updater key (pull mode)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PriceMessage {
+    pub asset: u8,
+    pub price: i64,
+    pub conf: u64,         // relay's confidence, carried into the event only
+    pub timestamp_ms: i64, // relay clock, checked like `client_ts_ms`
+    pub slot: u64,         // cluster slot the relay observed
+}
+
+impl PriceMessage {
+    /// Prefix of every signed message, so the signature cannot be reused
+    /// for anything else
+    pub const DOMAIN: &'static [u8] = b"oracle:price:v1";
+
+    /// Bytes the updater signs: `DOMAIN || program id || borsh(message)`
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::DOMAIN.len() + 32 + 33);
+        bytes.extend_from_slice(Self::DOMAIN);
+        bytes.extend_from_slice(crate::ID.as_ref());
+        self.serialize(&mut bytes).unwrap();
+        bytes
+    }
+}
+
+/// Apply a price message signed by the updater of slot `index`. The
+/// transaction must carry an Ed25519 sig-verify instruction for exactly this
+/// message and key right before this one; anyone may pay for it. Replays
+/// fail the same timestamp check as any other stale write.
+pub fn post_signed_price<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PostSignedPrice<'info>>,
+    index: u8,
+    message: PriceMessage,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut state = StateAccount::load_mut(ctx.accounts.state.as_ref())?;
+    let s = *state.header();
+    verify_ed25519(&ctx.accounts.instructions, &s.updater(index)?, &message.signing_bytes())?;
+    require!(s.paused == 0, OracleError::OraclePaused);
+    s.check_client_ts(message.timestamp_ms, &clock)?;
+    require!(message.slot <= clock.slot, OracleError::FutureSlot);
+
+    let entry = state.asset_mut(message.asset)?;
+    require!(!entry.is_halted(), OracleError::AssetHalted);
+    let mut remaining = ctx.remaining_accounts.iter();
+    write_slot(&s, entry, &mut remaining, index, message.price, message.timestamp_ms, &clock, false)?;
+
+    emit!(SignedPricePosted {
+        asset: message.asset,
+        index,
+        price: message.price,
+        conf: message.conf,
+        observed_slot: message.slot,
+        poster: ctx.accounts.payer.key(),
+    });
+    Ok(())
+}
+
+// Layout of the Ed25519 precompile's instruction data
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+/// Offset index meaning "in the Ed25519 instruction itself"
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Check that the previous instruction is an Ed25519 sig-verify of `message`
+/// by `signer`; see `check_ed25519`.
+fn verify_ed25519(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let ix = get_instruction_relative(-1, instructions).map_err(|_| error!(OracleError::MissingSigVerify))?;
+    check_ed25519(&ix, signer, message)
+}
+
+/// Check that `ix` is an Ed25519 sig-verify of `message` by `signer`, with
+/// everything it checks contained in its own data. The precompile already
+/// failed the transaction if the signature was bad.
+fn check_ed25519(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    require_keys_eq!(ix.program_id, ed25519_program::ID, OracleError::MissingSigVerify);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE && data[0] == 1,
+        OracleError::BadSigVerify
+    );
+    let field = |i: usize| {
+        let at = SIGNATURE_OFFSETS_START + 2 * i;
+        u16::from_le_bytes([data[at], data[at + 1]])
+    };
+    let (signature_offset, signature_ix) = (field(0) as usize, field(1));
+    let (pubkey_offset, pubkey_ix) = (field(2) as usize, field(3));
+    let (message_offset, message_size, message_ix) = (field(4) as usize, field(5) as usize, field(6));
+    require!(
+        signature_ix == CURRENT_INSTRUCTION
+            && pubkey_ix == CURRENT_INSTRUCTION
+            && message_ix == CURRENT_INSTRUCTION,
+        OracleError::BadSigVerify
+    );
+    require!(
+        data.len() >= signature_offset + SIGNATURE_SIZE
+            && data.len() >= pubkey_offset + PUBKEY_SIZE
+            && data.len() >= message_offset + message_size,
+        OracleError::BadSigVerify
+    );
+
+    require!(
+        data[pubkey_offset..pubkey_offset + PUBKEY_SIZE] == signer.to_bytes(),
+        OracleError::BadSigVerify
+    );
+    require!(
+        data[message_offset..message_offset + message_size] == *message,
+        OracleError::BadSigVerify
+    );
+    Ok(())
+}
+
+#[event]
+pub struct SignedPricePosted {
+    pub asset: u8,
+    pub index: u8,
+    pub price: i64,
+    pub conf: u64,
+    pub observed_slot: u64,
+    pub poster: Pubkey,   // fee payer that relayed the message
+}
+
+#[derive(Accounts)]
+pub struct PostSignedPrice<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    /// CHECK: The instructions sysvar, checked by address
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub payer: Signer<'info>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBKEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBKEY_OFFSET + PUBKEY_SIZE as u16;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + SIGNATURE_SIZE as u16;
+
+    /// Sig-verify instruction for `message` by `signer` laid out like the
+    /// Ed25519 precompile expects, all offsets pointing into its own data.
+    fn sig_verify(signer: &Pubkey, message: &[u8]) -> Instruction {
+        let offsets = [
+            SIGNATURE_OFFSET,
+            CURRENT_INSTRUCTION,
+            PUBKEY_OFFSET,
+            CURRENT_INSTRUCTION,
+            MESSAGE_OFFSET,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ];
+        let mut data = vec![1, 0];
+        for offset in offsets {
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7; SIGNATURE_SIZE]);
+        data.extend_from_slice(message);
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    /// Overwrite offsets field `i` of `ix`.
+    fn set_field(ix: &mut Instruction, i: usize, value: u16) {
+        let at = SIGNATURE_OFFSETS_START + 2 * i;
+        ix.data[at..at + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn message() -> Vec<u8> {
+        PriceMessage {
+            asset: 1,
+            price: 100_000,
+            conf: 10,
+            timestamp_ms: 1_700_000_000_000,
+            slot: 42,
+        }
+        .signing_bytes()
+    }
+
+    #[test]
+    fn accepts_matching_sig_verify() {
+        let signer = Pubkey::new_unique();
+        let message = message();
+        assert_eq!(check_ed25519(&sig_verify(&signer, &message), &signer, &message), Ok(()));
+    }
+
+    #[test]
+    fn rejects_other_program() {
+        let signer = Pubkey::new_unique();
+        let message = message();
+        let mut ix = sig_verify(&signer, &message);
+        ix.program_id = Pubkey::new_unique();
+        assert_eq!(check_ed25519(&ix, &signer, &message), Err(OracleError::MissingSigVerify.into()));
+    }
+
+    #[test]
+    fn rejects_offsets_into_other_instructions() {
+        let signer = Pubkey::new_unique();
+        let message = message();
+        // Signature, pubkey and message instruction indexes
+        for field in [1, 3, 6] {
+            let mut ix = sig_verify(&signer, &message);
+            set_field(&mut ix, field, 0);
+            assert_eq!(check_ed25519(&ix, &signer, &message), Err(OracleError::BadSigVerify.into()));
+        }
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let signer = Pubkey::new_unique();
+        let message = message();
+        let full = sig_verify(&signer, &message);
+        for len in [0, 1, SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE - 1, full.data.len() - 1] {
+            let mut ix = full.clone();
+            ix.data.truncate(len);
+            assert_eq!(check_ed25519(&ix, &signer, &message), Err(OracleError::BadSigVerify.into()));
+        }
+        // Message size pointing past the end of the data
+        let mut ix = full.clone();
+        set_field(&mut ix, 5, message.len() as u16 + 1);
+        assert_eq!(check_ed25519(&ix, &signer, &message), Err(OracleError::BadSigVerify.into()));
+    }
+
+    #[test]
+    fn rejects_wrong_pubkey_or_message() {
+        let signer = Pubkey::new_unique();
+        let message = message();
+        let ix = sig_verify(&signer, &message);
+        assert_eq!(
+            check_ed25519(&ix, &Pubkey::new_unique(), &message),
+            Err(OracleError::BadSigVerify.into())
+        );
+
+        let mut other = message.clone();
+        *other.last_mut().unwrap() ^= 1;
+        assert_eq!(check_ed25519(&ix, &signer, &other), Err(OracleError::BadSigVerify.into()));
+        // A prefix of the signed message is not the message either
+        assert_eq!(
+            check_ed25519(&ix, &signer, &message[..message.len() - 1]),
+            Err(OracleError::BadSigVerify.into())
+        );
+    }
+
+    #[test]
+    fn rejects_other_signature_counts() {
+        let signer = Pubkey::new_unique();
+        let message = message();
+        for count in [0, 2] {
+            let mut ix = sig_verify(&signer, &message);
+            ix.data[0] = count;
+            assert_eq!(check_ed25519(&ix, &signer, &message), Err(OracleError::BadSigVerify.into()));
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use crate::state::*;
+use super::admin::{apply_action, ActionAccounts};
+
+/// Queue an admin action to run once `State::timelock_secs` has passed
+pub fn queue_action(ctx: Context<QueueAction>, action: AdminAction) -> Result<()> {
+    require!(
+        !matches!(action, AdminAction::SetGovernance { .. }),
+        OracleError::GovernanceOnly
+    );
+    let now = Clock::get()?.unix_timestamp;
+    let (id, eta) = {
+        let s = &mut *ctx.accounts.state.load_mut()?;
+        require_keys_eq!(ctx.accounts.signer.key(), s.update_authority, OracleError::Unauthorized);
+        let id = s.queued_count;
+        s.queued_count += 1;
+        (id, now + s.timelock_secs as i64)
+    };
+
+    let q = &mut ctx.accounts.queued;
+    q.id = id;
+    q.eta = eta;
+    q.bump = ctx.bumps.queued;
+    q.action = action.clone();
+
+    emit!(ActionQueued { id, eta, action });
+    Ok(())
+}
+
+/// Run a queued action whose ETA has passed; the admin pays for any state
+/// growth and receives refunded rent. Accounts the action creates or closes
+/// go in `remaining_accounts`.
+pub fn execute_queued_action<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteQueuedAction<'info>>) -> Result<()> {
+    let a = &ctx.accounts;
+    {
+        let state = StateAccount::load(&a.state)?;
+        require_keys_eq!(a.signer.key(), state.header().update_authority, OracleError::Unauthorized);
+    }
+    require!(Clock::get()?.unix_timestamp >= a.queued.eta, OracleError::TimelockNotElapsed);
+
+    let accounts = ActionAccounts {
+        state: &a.state,
+        payer: Some(&a.signer),
+        system_program: Some(&a.system_program),
+        remaining: ctx.remaining_accounts,
+    };
+    apply_action(&accounts, a.queued.action.clone())?;
+
+    emit!(ActionExecuted { id: a.queued.id });
+    Ok(())
+}
+
+pub fn cancel_queued_action(ctx: Context<CancelQueuedAction>) -> Result<()> {
+    let s = ctx.accounts.state.load()?;
+    require_keys_eq!(ctx.accounts.signer.key(), s.update_authority, OracleError::Unauthorized);
+    emit!(ActionCancelled { id: ctx.accounts.queued.id });
+    Ok(())
+}
+
+#[event]
+pub struct ActionQueued {
+    pub id: u64,
+    pub eta: i64,
+    pub action: AdminAction,
+}
+
+#[event]
+pub struct ActionExecuted {
+    pub id: u64,
+}
+
+#[event]
+pub struct ActionCancelled {
+    pub id: u64,
+}
+
+#[derive(Accounts)]
+pub struct QueueAction<'info> {
+    #[account(mut, seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + QueuedAction::SIZE,
+        seeds = [QueuedAction::SEED, &state.load()?.queued_count.to_le_bytes()],
+        bump
+    )]
+    pub queued: Account<'info, QueuedAction>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteQueuedAction<'info> {
+    /// CHECK: Raw so that `CloseState` can hand it back to the system program;
+    /// the handler loads it through `StateAccount`
+    #[account(mut, seeds = [State::SEED], bump, owner = crate::ID)]
+    pub state: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = signer,
+        seeds = [QueuedAction::SEED, &queued.id.to_le_bytes()],
+        bump = queued.bump
+    )]
+    pub queued: Account<'info, QueuedAction>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQueuedAction<'info> {
+    #[account(seeds = [State::SEED], bump = state.load()?.bump)]
+    pub state: AccountLoader<'info, State>,
+    #[account(
+        mut,
+        close = signer,
+        seeds = [QueuedAction::SEED, &queued.id.to_le_bytes()],
+        bump = queued.bump
+    )]
+    pub queued: Account<'info, QueuedAction>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+}
@@ -1,427 +1,262 @@
+// anchor 0.31's #[program] expansion still calls AccountInfo::realloc
+#![allow(deprecated)]
+
 use anchor_lang::prelude::*;
-use std::str::FromStr;
 
-declare_id!("CcgTMiYkgVfz7cAGkD6835BqfycG5N5Y4aPPHYW1EvKx");
+pub mod consumer;
+pub mod errors;
+pub mod instructions;
+pub mod state;
 
-// Hard-coded per-parameter updaters (mainnet relays)
-const PARAM1_UPDATER: &str = "CGLezzdUpYmxiq3g5xdXxry8SWqwQbSxFJsdqfM13ro9"; // mn_relay1.json
-const PARAM2_UPDATER: &str = "FprJrTPJq9eKsVxEVhQCyRChEMaYzyTwcnK8aNfCae2D"; // mn_relay2.json
-const PARAM3_UPDATER: &str = "7FZvQQE1VDq2fFSuBmCCxmo8tPNm9LfYqF9BMkbyp1by"; // mn_relay3.json
-const PARAM4_UPDATER: &str = "55MyuYePgkwAExNqtdNY4zahSyiM3stjjRm3Ym36sTA8"; // Reserved for future use
-
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum Asset {
-    Btc = 1,
-    Eth = 2,
-    Sol = 3,
-    Hype = 4,
-    Zec = 5,
-    Tsla = 6,
-    Nvda = 7,
-    Mstr = 8,
-    Gold = 9,
-    Silver = 10,
-}
+use consumer::{Price, RoundData};
+use instructions::*;
+use state::{AdminAction, AssetStatus};
+
+declare_id!("CcgTMiYkgVfz7cAGkD6835BqfycG5N5Y4aPPHYW1EvKx");
 
 #[program]
 pub mod oracle {
     use super::*;
 
+    /// Create the `state_v2` account seeded with the default assets; signed by
+    /// the program upgrade authority
     pub fn initialize(ctx: Context<Initialize>, update_authority: Pubkey) -> Result<()> {
-        let s = &mut ctx.accounts.state;
-        s.update_authority = update_authority;
-        s.decimals = 6;
-        s.bump = ctx.bumps.state;
-        s.btc = Triplet::default();
-        s.eth = Triplet::default();
-        s.sol = Triplet::default();
-        s.hype = Triplet::default();
-        s.zec = Triplet::default();
-        s.tsla = Triplet::default();
-        s.nvda = Triplet::default();
-        s.mstr = Triplet::default();
-        s.gold = Triplet::default();
-        s.silver = Triplet::default();
-        Ok(())
-    }
-
-    pub fn set_price(
-        ctx: Context<SetPrice>,
+        instructions::initialize(ctx, update_authority)
+    }
+
+    /// Write one updater slot of a single asset
+    pub fn set_price<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetPrice<'info>>,
         asset: u8,
         index: u8,
         price: i64,
         client_ts_ms: i64,
+        seq: Option<u64>,
     ) -> Result<()> {
-        let signer = ctx.accounts.signer.key();
-
-        let expected = match index {
-            1 => Pubkey::from_str(PARAM1_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            2 => Pubkey::from_str(PARAM2_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            3 => Pubkey::from_str(PARAM3_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            4 => Pubkey::from_str(PARAM4_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            _ => return err!(OracleError::BadIndex),
-        };
-        require_keys_eq!(signer, expected, OracleError::UnauthorizedForIndex);
-
-        let s = &mut ctx.accounts.state;
-        let t = match asset {
-            x if x == Asset::Btc as u8 => &mut s.btc,
-            x if x == Asset::Eth as u8 => &mut s.eth,
-            x if x == Asset::Sol as u8 => &mut s.sol,
-            x if x == Asset::Hype as u8 => &mut s.hype,
-            x if x == Asset::Zec as u8 => &mut s.zec,
-            x if x == Asset::Tsla as u8 => &mut s.tsla,
-            x if x == Asset::Nvda as u8 => &mut s.nvda,
-            x if x == Asset::Mstr as u8 => &mut s.mstr,
-            x if x == Asset::Gold as u8 => &mut s.gold,
-            x if x == Asset::Silver as u8 => &mut s.silver,
-            _ => return err!(OracleError::BadAsset),
-        };
-
-        match index {
-            1 => { t.param1 = price; t.ts1 = client_ts_ms; }
-            2 => { t.param2 = price; t.ts2 = client_ts_ms; }
-            3 => { t.param3 = price; t.ts3 = client_ts_ms; }
-            4 => { t.param4 = price; t.ts4 = client_ts_ms; }
-            _ => unreachable!(),
-        }
-
-        emit!(PriceUpdated {
-            asset,
-            index,
-            price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot: Clock::get()?.slot,
-        });
-
-        Ok(())
-    }
-
-    pub fn batch_set_prices(
-        ctx: Context<SetPrice>,
+        instructions::set_price(ctx, asset, index, price, client_ts_ms, seq)
+    }
+
+    /// Write one updater slot of each listed asset
+    pub fn batch_set_prices<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SetPrice<'info>>,
         index: u8,
-        btc_price: i64,
-        eth_price: i64,
-        sol_price: i64,
-        hype_price: i64,
-        zec_price: i64,
-        tsla_price: i64,
-        nvda_price: i64,
-        mstr_price: i64,
-        gold_price: i64,
-        silver_price: i64,
+        updates: Vec<PriceUpdate>,
         client_ts_ms: i64,
+        seq: Option<u64>,
+    ) -> Result<()> {
+        instructions::batch_set_prices(ctx, index, updates, client_ts_ms, seq)
+    }
+
+    /// Write an updater slot from an Ed25519-signed price message (pull mode)
+    pub fn post_signed_price<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PostSignedPrice<'info>>,
+        index: u8,
+        message: PriceMessage,
+    ) -> Result<()> {
+        instructions::post_signed_price(ctx, index, message)
+    }
+
+    /// Validated price of one asset, returned through return data
+    pub fn get_price(ctx: Context<ReadPrice>, asset: u8, max_age_secs: u64) -> Result<Price> {
+        instructions::get_price(ctx, asset, max_age_secs)
+    }
+
+    /// Validated prices of several assets, returned through return data
+    pub fn get_prices(ctx: Context<ReadPrice>, assets: Vec<u8>, max_age_secs: u64) -> Result<Vec<Price>> {
+        instructions::get_prices(ctx, assets, max_age_secs)
+    }
+
+    /// Latest finalized round of one asset, returned through return data
+    pub fn latest_round_data(ctx: Context<ReadPrice>, asset: u8) -> Result<RoundData> {
+        instructions::latest_round_data(ctx, asset)
+    }
+
+    /// Propose a new admin; takes effect when it calls `accept_authority`
+    pub fn propose_authority(ctx: Context<SetUpdateAuthority>, new_auth: Pubkey) -> Result<()> {
+        instructions::propose_authority(ctx, new_auth)
+    }
+
+    /// Become the admin; signed by the proposed authority
+    pub fn accept_authority(ctx: Context<SetUpdateAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    /// Withdraw a pending admin proposal
+    pub fn cancel_authority_transfer(ctx: Context<SetUpdateAuthority>) -> Result<()> {
+        instructions::cancel_authority_transfer(ctx)
+    }
+
+    /// Configure the quorum and staleness window used for aggregation
+    pub fn set_aggregation_config(
+        ctx: Context<SetUpdateAuthority>,
+        min_quorum: u8,
+        stale_after_secs: u32,
+    ) -> Result<()> {
+        instructions::set_aggregation_config(ctx, min_quorum, stale_after_secs)
+    }
+
+    /// Configure how far `client_ts_ms` may drift from the cluster clock
+    pub fn set_max_clock_drift(ctx: Context<SetUpdateAuthority>, max_clock_drift_ms: u32) -> Result<()> {
+        instructions::set_max_clock_drift(ctx, max_clock_drift_ms)
+    }
+
+    /// Configure the time constant of the per-asset EMA
+    pub fn set_ema_period(ctx: Context<SetUpdateAuthority>, ema_period_secs: u32) -> Result<()> {
+        instructions::set_ema_period(ctx, ema_period_secs)
+    }
+
+    /// Assign a key to an empty updater slot
+    pub fn add_updater(ctx: Context<SetUpdateAuthority>, index: u8, updater: Pubkey) -> Result<()> {
+        instructions::add_updater(ctx, index, updater)
+    }
+
+    /// Clear an updater slot
+    pub fn remove_updater(ctx: Context<SetUpdateAuthority>, index: u8) -> Result<()> {
+        instructions::remove_updater(ctx, index)
+    }
+
+    /// Replace the key of an assigned updater slot
+    pub fn rotate_updater(ctx: Context<SetUpdateAuthority>, index: u8, new_updater: Pubkey) -> Result<()> {
+        instructions::rotate_updater(ctx, index, new_updater)
+    }
+
+    /// Register a new asset in the asset table
+    pub fn add_asset(ctx: Context<AddAsset>, id: u8, symbol: String, decimals: u8) -> Result<()> {
+        instructions::add_asset(ctx, id, symbol, decimals)
+    }
+
+    /// Change an asset's decimals, rescaling its stored prices
+    pub fn set_asset_decimals(ctx: Context<SetUpdateAuthority>, id: u8, decimals: u8) -> Result<()> {
+        instructions::set_asset_decimals(ctx, id, decimals)
+    }
+
+    /// Attach a price history ring buffer to an asset
+    pub fn init_history(ctx: Context<InitHistory>, asset: u8) -> Result<()> {
+        instructions::init_history(ctx, asset)
+    }
+
+    /// Detach and close an asset's price history
+    pub fn close_history(ctx: Context<CloseHistory>, asset: u8) -> Result<()> {
+        instructions::close_history(ctx, asset)
+    }
+
+    /// Attach a Pyth `PriceUpdateV2`-layout mirror account to an asset
+    pub fn init_pyth_mirror(ctx: Context<InitPythMirror>, asset: u8, feed_id: [u8; 32]) -> Result<()> {
+        instructions::init_pyth_mirror(ctx, asset, feed_id)
+    }
+
+    /// Detach and close an asset's Pyth mirror
+    pub fn close_pyth_mirror(ctx: Context<ClosePythMirror>, asset: u8) -> Result<()> {
+        instructions::close_pyth_mirror(ctx, asset)
+    }
+
+    /// Update Pyth mirrors' verification level after a pause or status change
+    pub fn sync_pyth_mirrors<'info>(
+        ctx: Context<'_, '_, '_, 'info, SyncPythMirrors<'info>>,
+        assets: Vec<u8>,
+    ) -> Result<()> {
+        instructions::sync_pyth_mirrors(ctx, assets)
+    }
+
+    /// Configure an asset's price deviation limits
+    pub fn set_circuit_breaker(
+        ctx: Context<SetUpdateAuthority>,
+        id: u8,
+        max_move_bps: u16,
+        max_window_move_bps: u16,
+        window_secs: u32,
+        halt_on_breach: bool,
     ) -> Result<()> {
-        let signer = ctx.accounts.signer.key();
-
-        let expected = match index {
-            1 => Pubkey::from_str(PARAM1_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            2 => Pubkey::from_str(PARAM2_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            3 => Pubkey::from_str(PARAM3_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            4 => Pubkey::from_str(PARAM4_UPDATER).map_err(|_| error!(OracleError::BadKey))?,
-            _ => return err!(OracleError::BadIndex),
-        };
-        require_keys_eq!(signer, expected, OracleError::UnauthorizedForIndex);
-
-        let s = &mut ctx.accounts.state;
-        let slot = Clock::get()?.slot;
-
-        // Update all 10 assets in one instruction
-        match index {
-            1 => {
-                s.btc.param1 = btc_price;
-                s.btc.ts1 = client_ts_ms;
-                s.eth.param1 = eth_price;
-                s.eth.ts1 = client_ts_ms;
-                s.sol.param1 = sol_price;
-                s.sol.ts1 = client_ts_ms;
-                s.hype.param1 = hype_price;
-                s.hype.ts1 = client_ts_ms;
-                s.zec.param1 = zec_price;
-                s.zec.ts1 = client_ts_ms;
-                s.tsla.param1 = tsla_price;
-                s.tsla.ts1 = client_ts_ms;
-                s.nvda.param1 = nvda_price;
-                s.nvda.ts1 = client_ts_ms;
-                s.mstr.param1 = mstr_price;
-                s.mstr.ts1 = client_ts_ms;
-                s.gold.param1 = gold_price;
-                s.gold.ts1 = client_ts_ms;
-                s.silver.param1 = silver_price;
-                s.silver.ts1 = client_ts_ms;
-            }
-            2 => {
-                s.btc.param2 = btc_price;
-                s.btc.ts2 = client_ts_ms;
-                s.eth.param2 = eth_price;
-                s.eth.ts2 = client_ts_ms;
-                s.sol.param2 = sol_price;
-                s.sol.ts2 = client_ts_ms;
-                s.hype.param2 = hype_price;
-                s.hype.ts2 = client_ts_ms;
-                s.zec.param2 = zec_price;
-                s.zec.ts2 = client_ts_ms;
-                s.tsla.param2 = tsla_price;
-                s.tsla.ts2 = client_ts_ms;
-                s.nvda.param2 = nvda_price;
-                s.nvda.ts2 = client_ts_ms;
-                s.mstr.param2 = mstr_price;
-                s.mstr.ts2 = client_ts_ms;
-                s.gold.param2 = gold_price;
-                s.gold.ts2 = client_ts_ms;
-                s.silver.param2 = silver_price;
-                s.silver.ts2 = client_ts_ms;
-            }
-            3 => {
-                s.btc.param3 = btc_price;
-                s.btc.ts3 = client_ts_ms;
-                s.eth.param3 = eth_price;
-                s.eth.ts3 = client_ts_ms;
-                s.sol.param3 = sol_price;
-                s.sol.ts3 = client_ts_ms;
-                s.hype.param3 = hype_price;
-                s.hype.ts3 = client_ts_ms;
-                s.zec.param3 = zec_price;
-                s.zec.ts3 = client_ts_ms;
-                s.tsla.param3 = tsla_price;
-                s.tsla.ts3 = client_ts_ms;
-                s.nvda.param3 = nvda_price;
-                s.nvda.ts3 = client_ts_ms;
-                s.mstr.param3 = mstr_price;
-                s.mstr.ts3 = client_ts_ms;
-                s.gold.param3 = gold_price;
-                s.gold.ts3 = client_ts_ms;
-                s.silver.param3 = silver_price;
-                s.silver.ts3 = client_ts_ms;
-            }
-            4 => {
-                s.btc.param4 = btc_price;
-                s.btc.ts4 = client_ts_ms;
-                s.eth.param4 = eth_price;
-                s.eth.ts4 = client_ts_ms;
-                s.sol.param4 = sol_price;
-                s.sol.ts4 = client_ts_ms;
-                s.hype.param4 = hype_price;
-                s.hype.ts4 = client_ts_ms;
-                s.zec.param4 = zec_price;
-                s.zec.ts4 = client_ts_ms;
-                s.tsla.param4 = tsla_price;
-                s.tsla.ts4 = client_ts_ms;
-                s.nvda.param4 = nvda_price;
-                s.nvda.ts4 = client_ts_ms;
-                s.mstr.param4 = mstr_price;
-                s.mstr.ts4 = client_ts_ms;
-                s.gold.param4 = gold_price;
-                s.gold.ts4 = client_ts_ms;
-                s.silver.param4 = silver_price;
-                s.silver.ts4 = client_ts_ms;
-            }
-            _ => unreachable!(),
-        }
-
-        // Emit events for all assets
-        emit!(PriceUpdated {
-            asset: Asset::Btc as u8,
-            index,
-            price: btc_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Eth as u8,
-            index,
-            price: eth_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Sol as u8,
-            index,
-            price: sol_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Hype as u8,
-            index,
-            price: hype_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Zec as u8,
-            index,
-            price: zec_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Tsla as u8,
-            index,
-            price: tsla_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Nvda as u8,
-            index,
-            price: nvda_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Mstr as u8,
-            index,
-            price: mstr_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Gold as u8,
-            index,
-            price: gold_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-        emit!(PriceUpdated {
-            asset: Asset::Silver as u8,
-            index,
-            price: silver_price,
-            decimals: s.decimals,
-            client_ts_ms,
-            slot,
-        });
-
-        Ok(())
-    }
-
-    pub fn set_update_authority(ctx: Context<SetUpdateAuthority>, new_auth: Pubkey) -> Result<()> {
-        let s = &mut ctx.accounts.state;
-        require_keys_eq!(ctx.accounts.signer.key(), s.update_authority, OracleError::Unauthorized);
-        s.update_authority = new_auth;
-        Ok(())
+        instructions::set_circuit_breaker(ctx, id, max_move_bps, max_window_move_bps, window_secs, halt_on_breach)
+    }
+
+    /// Configure how far an updater slot may stray from the others before it
+    /// is excluded from the aggregate
+    pub fn set_outlier_threshold(ctx: Context<SetUpdateAuthority>, id: u8, outlier_bps: u16) -> Result<()> {
+        instructions::set_outlier_threshold(ctx, id, outlier_bps)
+    }
+
+    /// Configure how many agreeing updaters finalize a round of an asset
+    pub fn set_round_config(
+        ctx: Context<SetUpdateAuthority>,
+        id: u8,
+        min_answers: u8,
+        tolerance_bps: u16,
+    ) -> Result<()> {
+        instructions::set_round_config(ctx, id, min_answers, tolerance_bps)
+    }
+
+    /// Resume updates of an asset halted by its circuit breaker
+    pub fn resume_asset<'info>(ctx: Context<'_, '_, '_, 'info, SetUpdateAuthority<'info>>, id: u8) -> Result<()> {
+        instructions::resume_asset(ctx, id)
+    }
+
+    /// Set an asset's trading status (active, halted, settlement-only)
+    pub fn set_asset_status<'info>(ctx: Context<'_, '_, '_, 'info, SetUpdateAuthority<'info>>, id: u8, status: AssetStatus) -> Result<()> {
+        instructions::set_asset_status(ctx, id, status)
+    }
+
+    /// Globally pause or unpause price updates and reads
+    pub fn set_paused(ctx: Context<SetUpdateAuthority>, paused: bool) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    /// Configure the delay timelocked admin actions must wait out
+    pub fn set_timelock(ctx: Context<SetUpdateAuthority>, timelock_secs: u32) -> Result<()> {
+        instructions::set_timelock(ctx, timelock_secs)
+    }
+
+    /// Remove an asset from the asset table
+    pub fn retire_asset(ctx: Context<RetireAsset>, id: u8) -> Result<()> {
+        instructions::retire_asset(ctx, id)
     }
 
     pub fn close_state(ctx: Context<CloseState>) -> Result<()> {
-        // Manually transfer lamports and zero out data
-        let state_lamports = ctx.accounts.state.lamports();
-        **ctx.accounts.state.lamports.borrow_mut() = 0;
-        **ctx.accounts.recipient.lamports.borrow_mut() += state_lamports;
-        Ok(())
+        instructions::close_state(ctx)
     }
-}
 
-#[event]
-pub struct PriceUpdated {
-    pub asset: u8,        // 1=BTC, 2=ETH, 3=SOL, 4=HYPE, 5=ZEC
-    pub index: u8,        // 1,2,3,4
-    pub price: i64,
-    pub decimals: u8,     // 6
-    pub client_ts_ms: i64,
-    pub slot: u64,
-}
+    /// Hand the admin role to an M-of-N governance account
+    pub fn init_governance(ctx: Context<InitGovernance>, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::init_governance(ctx, signers, threshold)
+    }
 
-#[account]
-pub struct State {
-    pub update_authority: Pubkey, // 32
-    pub btc: Triplet,             // 64
-    pub eth: Triplet,             // 64
-    pub sol: Triplet,             // 64
-    pub hype: Triplet,            // 64
-    pub zec: Triplet,             // 64
-    pub tsla: Triplet,            // 64
-    pub nvda: Triplet,            // 64
-    pub mstr: Triplet,            // 64
-    pub gold: Triplet,            // 64
-    pub silver: Triplet,          // 64
-    pub decimals: u8,             // 1
-    pub bump: u8,                 // 1
-}
-impl State {
-    pub const SIZE: usize = 32 + (Triplet::SIZE * 10) + 1 + 1; // 32 + 640 + 2 = 674
-}
+    /// Propose an admin action to the governance signers
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: AdminAction) -> Result<()> {
+        instructions::create_proposal(ctx, action)
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
-pub struct Triplet {
-    pub param1: i64,
-    pub param2: i64,
-    pub param3: i64,
-    pub param4: i64,
-    pub ts1: i64,
-    pub ts2: i64,
-    pub ts3: i64,
-    pub ts4: i64,
-}
-impl Triplet {
-    pub const SIZE: usize = 8 * 8; // 64
-}
+    /// Approve a governance proposal
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        instructions::approve_proposal(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + State::SIZE,
-        seeds = [b"state_v2"],   // <<< CHANGED
-        bump
-    )]
-    pub state: Account<'info, State>,
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
+    /// Execute a governance proposal that reached its threshold
+    pub fn execute_proposal<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>) -> Result<()> {
+        instructions::execute_proposal(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct SetPrice<'info> {
-    #[account(mut, seeds = [b"state_v2"], bump = state.bump)] // <<< CHANGED
-    pub state: Account<'info, State>,
-    pub signer: Signer<'info>,
-}
+    /// Withdraw a governance proposal (proposer only)
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        instructions::cancel_proposal(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct SetUpdateAuthority<'info> {
-    #[account(mut, seeds = [b"state_v2"], bump = state.bump)] // <<< CHANGED
-    pub state: Account<'info, State>,
-    pub signer: Signer<'info>,
-}
+    /// Veto a timelocked governance proposal before its ETA
+    pub fn veto_proposal(ctx: Context<VetoProposal>) -> Result<()> {
+        instructions::veto_proposal(ctx)
+    }
 
-#[derive(Accounts)]
-pub struct CloseState<'info> {
-    /// CHECK: We use AccountInfo instead of Account to avoid deserialization
-    /// This allows closing accounts with old structure
-    #[account(
-        mut,
-        seeds = [b"state_v2"],
-        bump
-    )]
-    pub state: UncheckedAccount<'info>,
-    pub authority: Signer<'info>,
-    /// CHECK: Receives the lamports from the closed account
-    #[account(mut)]
-    pub recipient: SystemAccount<'info>,
-}
+    /// Queue a timelocked admin action
+    pub fn queue_action(ctx: Context<QueueAction>, action: AdminAction) -> Result<()> {
+        instructions::queue_action(ctx, action)
+    }
 
-#[error_code]
-pub enum OracleError {
-    #[msg("Unauthorized (admin)")]
-    Unauthorized,
-    #[msg("Bad asset (must be 1=BTC,2=ETH,3=SOL,4=HYPE,5=ZEC,6=TSLA,7=NVDA,8=MSTR,9=GOLD,10=SILVER)")]
-    BadAsset,
-    #[msg("Index must be 1, 2, 3, or 4")]
-    BadIndex,
-    #[msg("Signer not authorized for the requested index")]
-    UnauthorizedForIndex,
-    #[msg("Bad key literal")]
-    BadKey,
-}
+    /// Run a queued admin action once its ETA has passed
+    pub fn execute_queued_action<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteQueuedAction<'info>>) -> Result<()> {
+        instructions::execute_queued_action(ctx)
+    }
 
+    /// Drop a queued admin action
+    pub fn cancel_queued_action(ctx: Context<CancelQueuedAction>) -> Result<()> {
+        instructions::cancel_queued_action(ctx)
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+
+/// Per-asset limits on how far a single update may move the aggregate. A
+/// limit of 0 disables that check.
+#[zero_copy]
+#[derive(Default)]
+pub struct CircuitBreaker {
+    pub max_move_bps: u16,        // 2, vs the current aggregate
+    pub max_window_move_bps: u16, // 2, vs the aggregate at the start of the window
+    pub window_secs: u32,         // 4
+    pub ref_price: i64,           // 8, aggregate at the start of the current window
+    pub ref_ts: i64,              // 8, cluster unix_timestamp the window started (0 = none)
+    pub halt_on_breach: u8,       // 1, 1 = halt the asset, 0 = reject the update (skip it in a batch)
+    pub _padding: [u8; 7],        // 7
+}
+
+impl CircuitBreaker {
+    /// Check the candidate aggregate `price` against both limits, starting a
+    /// new window first when the current one has run out. `last` is the
+    /// current aggregate, if any; without one there is nothing to compare
+    /// against. Returns the reference price that was breached.
+    pub fn check(&mut self, price: i64, last: Option<i64>, now: i64) -> Option<i64> {
+        let last = last?;
+        if self.max_move_bps > 0 && exceeds(price, last, self.max_move_bps) {
+            return Some(last);
+        }
+        if self.max_window_move_bps > 0 {
+            if self.ref_ts == 0 || now.saturating_sub(self.ref_ts) >= self.window_secs as i64 {
+                self.ref_price = last;
+                self.ref_ts = now;
+            }
+            if exceeds(price, self.ref_price, self.max_window_move_bps) {
+                return Some(self.ref_price);
+            }
+        }
+        None
+    }
+
+    /// Forget the window reference; the next check starts a fresh window.
+    pub fn reset_window(&mut self) {
+        self.ref_price = 0;
+        self.ref_ts = 0;
+    }
+}
+
+/// Whether `price` is more than `max_bps` basis points away from `reference`.
+pub(crate) fn exceeds(price: i64, reference: i64, max_bps: u16) -> bool {
+    (price as i128 - reference as i128).abs() * 10_000 > (reference as i128).abs() * max_bps as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(max_move_bps: u16, max_window_move_bps: u16, window_secs: u32) -> CircuitBreaker {
+        CircuitBreaker { max_move_bps, max_window_move_bps, window_secs, ..Default::default() }
+    }
+
+    #[test]
+    fn first_update_passes() {
+        let mut b = breaker(100, 100, 60);
+        // Nothing to compare against yet, and no window is started
+        assert_eq!(b.check(1_000_000, None, 1_000), None);
+        assert_eq!((b.ref_price, b.ref_ts), (0, 0));
+    }
+
+    #[test]
+    fn per_update_breach() {
+        let mut b = breaker(100, 0, 0);
+        // 1% of 10_000 is 100: exactly at the limit passes, one more does not
+        assert_eq!(b.check(10_100, Some(10_000), 1_000), None);
+        assert_eq!(b.check(9_900, Some(10_000), 1_000), None);
+        assert_eq!(b.check(10_101, Some(10_000), 1_000), Some(10_000));
+        assert_eq!(b.check(9_899, Some(10_000), 1_000), Some(10_000));
+        // A disabled limit never trips
+        assert_eq!(breaker(0, 0, 0).check(1, Some(10_000), 1_000), None);
+    }
+
+    #[test]
+    fn window_accumulates_and_rolls_over() {
+        let mut b = breaker(0, 100, 60);
+        assert_eq!(b.check(10_050, Some(10_000), 1_000), None);
+        assert_eq!((b.ref_price, b.ref_ts), (10_000, 1_000));
+
+        // Each step is small, but together they pass 1% of the window start
+        assert_eq!(b.check(10_101, Some(10_050), 1_059), Some(10_000));
+        assert_eq!((b.ref_price, b.ref_ts), (10_000, 1_000));
+
+        // At window_secs the window restarts from the current aggregate
+        assert_eq!(b.check(10_101, Some(10_050), 1_060), None);
+        assert_eq!((b.ref_price, b.ref_ts), (10_050, 1_060));
+    }
+
+    #[test]
+    fn reset_window_restarts_from_the_current_aggregate() {
+        let mut b = breaker(0, 100, 3_600);
+        assert_eq!(b.check(10_000, Some(10_000), 1_000), None);
+        assert_eq!(b.check(20_000, Some(10_000), 1_010), Some(10_000));
+
+        // After a resume or a decimals change the old reference means nothing
+        b.reset_window();
+        assert_eq!((b.ref_price, b.ref_ts), (0, 0));
+        assert_eq!(b.check(20_100, Some(20_000), 1_020), None);
+        assert_eq!((b.ref_price, b.ref_ts), (20_000, 1_020));
+    }
+}
@@ -0,0 +1,291 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use super::AssetStatus;
+
+/// Most signers a `Governance` can hold; approvals are a `u16` bitmask
+pub const MAX_SIGNERS: usize = 10;
+
+/// An admin operation, applied either directly by the admin or by
+/// governance once a `Proposal` carrying it is approved.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum AdminAction {
+    SetAggregationConfig { min_quorum: u8, stale_after_secs: u32 },
+    SetMaxClockDrift { max_clock_drift_ms: u32 },
+    SetEmaPeriod { ema_period_secs: u32 },
+    AddUpdater { index: u8, updater: Pubkey },
+    RemoveUpdater { index: u8 },
+    RotateUpdater { index: u8, new_updater: Pubkey },
+    AddAsset { id: u8, symbol: String, decimals: u8 },
+    SetAssetDecimals { id: u8, decimals: u8 },
+    SetCircuitBreaker {
+        id: u8,
+        max_move_bps: u16,
+        max_window_move_bps: u16,
+        window_secs: u32,
+        halt_on_breach: bool,
+    },
+    ResumeAsset { id: u8 },
+    SetAssetStatus { id: u8, status: AssetStatus },
+    SetPaused { paused: bool },
+    RetireAsset { id: u8 },
+    ProposeAuthority { new_authority: Pubkey },
+    CancelAuthorityTransfer,
+    CloseState,
+    /// Replace the governance signer set; only valid as a proposal
+    SetGovernance { signers: Vec<Pubkey>, threshold: u8 },
+    SetTimelock { timelock_secs: u32 },
+    SetOutlierThreshold { id: u8, outlier_bps: u16 },
+    SetRoundConfig { id: u8, min_answers: u8, tolerance_bps: u16 },
+    InitHistory { asset: u8 },
+    CloseHistory { asset: u8 },
+    /// Hand the admin role to a new `Governance` with this signer set
+    InitGovernance { signers: Vec<Pubkey>, threshold: u8 },
+    InitPythMirror { asset: u8, feed_id: [u8; 32] },
+    ClosePythMirror { asset: u8 },
+}
+
+impl AdminAction {
+    /// Borsh size of the largest variants (`SetGovernance` and `InitGovernance`
+    /// with `MAX_SIGNERS` signers)
+    pub const MAX_SIZE: usize = 1 + 4 + 32 * MAX_SIGNERS + 1;
+
+    /// Whether the action has to wait out `State::timelock_secs`. Pausing
+    /// and status changes stay instant so incidents can be contained.
+    pub fn is_timelocked(&self) -> bool {
+        !matches!(
+            self,
+            AdminAction::SetPaused { .. }
+                | AdminAction::SetAssetStatus { .. }
+                | AdminAction::ResumeAsset { .. }
+                | AdminAction::CancelAuthorityTransfer
+        )
+    }
+}
+
+/// M-of-N admin (`[b"governance"]` PDA). Once `init_governance` has run it
+/// is the state's `update_authority`, and admin actions go through proposals.
+#[account]
+pub struct Governance {
+    pub signers: Vec<Pubkey>, // 4 + 32 * MAX_SIGNERS
+    pub threshold: u8,        // 1, approvals needed to execute
+    pub epoch: u32,           // 4, bumped when the signer set changes; older proposals lapse
+    pub proposal_count: u64,  // 8, id of the next proposal
+    pub bump: u8,             // 1
+}
+
+impl Governance {
+    pub const SEED: &'static [u8] = b"governance";
+    pub const SIZE: usize = 4 + 32 * MAX_SIGNERS + 1 + 4 + 8 + 1;
+
+    /// Check a signer set: 1..=MAX_SIGNERS distinct non-default keys and a
+    /// threshold between 1 and the number of signers.
+    pub fn validate(signers: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !signers.is_empty() && signers.len() <= MAX_SIGNERS,
+            OracleError::BadSignerSet
+        );
+        for (i, signer) in signers.iter().enumerate() {
+            require_keys_neq!(*signer, Pubkey::default(), OracleError::BadSignerSet);
+            require!(!signers[..i].contains(signer), OracleError::BadSignerSet);
+        }
+        require!(
+            threshold > 0 && threshold as usize <= signers.len(),
+            OracleError::BadThreshold
+        );
+        Ok(())
+    }
+
+    /// Position of `key` in the signer set.
+    pub fn signer_index(&self, key: &Pubkey) -> Result<usize> {
+        self.signers
+            .iter()
+            .position(|s| s == key)
+            .ok_or_else(|| error!(OracleError::NotGovernanceSigner))
+    }
+
+    /// Install a new signer set (already validated). The epoch moves on, so
+    /// proposals approved by the old set lapse.
+    pub fn set_signers(&mut self, signers: Vec<Pubkey>, threshold: u8) {
+        self.signers = signers;
+        self.threshold = threshold;
+        self.epoch += 1;
+    }
+
+    /// Vetoes that cancel a proposal: enough signers that the others can no
+    /// longer reach the threshold.
+    pub fn veto_threshold(&self) -> u32 {
+        (self.signers.len() + 1 - self.threshold as usize) as u32
+    }
+}
+
+/// A pending admin action (`[b"proposal", id]` PDA), closed to its proposer
+/// when executed or cancelled.
+#[account]
+pub struct Proposal {
+    pub id: u64,             // 8
+    pub proposer: Pubkey,    // 32
+    pub epoch: u32,          // 4, governance epoch the approvals refer to
+    pub approvals: u16,      // 2, bit i set = signers[i] approved
+    pub vetoes: u16,         // 2, bit i set = signers[i] vetoed
+    pub bump: u8,            // 1
+    pub eta: i64,            // 8, earliest execution of a timelocked action; 0 until approved
+    pub action: AdminAction, // up to AdminAction::MAX_SIZE
+}
+
+impl Proposal {
+    pub const SEED: &'static [u8] = b"proposal";
+    pub const SIZE: usize = 8 + 32 + 4 + 2 + 2 + 1 + 8 + AdminAction::MAX_SIZE;
+
+    pub fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
+
+    pub fn veto_count(&self) -> u32 {
+        self.vetoes.count_ones()
+    }
+
+    /// Record `signer`'s approval.
+    pub fn approve(&mut self, g: &Governance, signer: &Pubkey) -> Result<()> {
+        require!(self.epoch == g.epoch, OracleError::StaleProposal);
+        let bit = 1 << g.signer_index(signer)?;
+        require!(self.approvals & bit == 0, OracleError::AlreadyApproved);
+        self.approvals |= bit;
+        Ok(())
+    }
+
+    /// Record `signer`'s veto at `now`. Only timelocked actions can be
+    /// vetoed, and only before their ETA; the others run as soon as they are
+    /// approved. Returns whether the vetoes reached `veto_threshold`, which
+    /// cancels the proposal.
+    pub fn veto(&mut self, g: &Governance, signer: &Pubkey, now: i64) -> Result<bool> {
+        require!(self.epoch == g.epoch, OracleError::StaleProposal);
+        require!(self.action.is_timelocked(), OracleError::NotVetoable);
+        require!(self.eta == 0 || now < self.eta, OracleError::VetoClosed);
+        let bit = 1 << g.signer_index(signer)?;
+        require!(self.vetoes & bit == 0, OracleError::AlreadyVetoed);
+        self.vetoes |= bit;
+        Ok(self.veto_count() >= g.veto_threshold())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(n: u8) -> Vec<Pubkey> {
+        (1..=n).map(|i| Pubkey::new_from_array([i; 32])).collect()
+    }
+
+    fn governance(n: u8, threshold: u8) -> Governance {
+        Governance { signers: keys(n), threshold, epoch: 0, proposal_count: 0, bump: 0 }
+    }
+
+    fn proposal(g: &Governance, action: AdminAction) -> Proposal {
+        Proposal {
+            id: 0,
+            proposer: g.signers[0],
+            epoch: g.epoch,
+            approvals: 1,
+            vetoes: 0,
+            bump: 0,
+            eta: 0,
+            action,
+        }
+    }
+
+    #[test]
+    fn validate_signer_set() {
+        assert_eq!(Governance::validate(&keys(3), 2), Ok(()));
+        assert_eq!(Governance::validate(&keys(MAX_SIGNERS as u8), 1), Ok(()));
+
+        assert_eq!(Governance::validate(&[], 0), Err(OracleError::BadSignerSet.into()));
+        let too_many = keys(MAX_SIGNERS as u8 + 1);
+        assert_eq!(Governance::validate(&too_many, 1), Err(OracleError::BadSignerSet.into()));
+        let mut duplicate = keys(3);
+        duplicate[2] = duplicate[0];
+        assert_eq!(Governance::validate(&duplicate, 2), Err(OracleError::BadSignerSet.into()));
+        let mut default = keys(3);
+        default[1] = Pubkey::default();
+        assert_eq!(Governance::validate(&default, 2), Err(OracleError::BadSignerSet.into()));
+    }
+
+    #[test]
+    fn validate_threshold_bounds() {
+        assert_eq!(Governance::validate(&keys(3), 1), Ok(()));
+        assert_eq!(Governance::validate(&keys(3), 3), Ok(()));
+        assert_eq!(Governance::validate(&keys(3), 0), Err(OracleError::BadThreshold.into()));
+        assert_eq!(Governance::validate(&keys(3), 4), Err(OracleError::BadThreshold.into()));
+    }
+
+    #[test]
+    fn approvals_are_a_bitmask_of_signers() {
+        let g = governance(3, 2);
+        let mut p = proposal(&g, AdminAction::SetPaused { paused: true });
+        p.approve(&g, &g.signers[2]).unwrap();
+        assert_eq!((p.approvals, p.approval_count()), (0b101, 2));
+
+        assert_eq!(p.approve(&g, &g.signers[0]), Err(OracleError::AlreadyApproved.into()));
+        assert_eq!(p.approve(&g, &g.signers[2]), Err(OracleError::AlreadyApproved.into()));
+        assert_eq!(p.approve(&g, &Pubkey::new_unique()), Err(OracleError::NotGovernanceSigner.into()));
+        assert_eq!(p.approvals, 0b101);
+    }
+
+    #[test]
+    fn new_signer_set_lapses_open_proposals() {
+        let mut g = governance(3, 2);
+        let mut p = proposal(&g, AdminAction::SetTimelock { timelock_secs: 60 });
+        g.set_signers(keys(4), 3);
+        assert_eq!(g.epoch, 1);
+
+        // Signer 2 is in both sets, but its approval would count toward a
+        // proposal the new set never saw
+        assert_eq!(p.approve(&g, &g.signers[1]), Err(OracleError::StaleProposal.into()));
+        assert_eq!(p.veto(&g, &g.signers[1], 0), Err(OracleError::StaleProposal.into()));
+        assert_eq!((p.approvals, p.vetoes), (1, 0));
+    }
+
+    #[test]
+    fn veto_needs_enough_signers_to_block_the_threshold() {
+        // 2-of-4: three vetoes leave only one signer, below the threshold
+        let g = governance(4, 2);
+        assert_eq!(g.veto_threshold(), 3);
+        let mut p = proposal(&g, AdminAction::SetTimelock { timelock_secs: 60 });
+        p.eta = 1_000;
+        assert_eq!(p.veto(&g, &g.signers[1], 900), Ok(false));
+        assert_eq!(p.veto(&g, &g.signers[1], 900), Err(OracleError::AlreadyVetoed.into()));
+        assert_eq!(p.veto(&g, &g.signers[2], 900), Ok(false));
+        assert_eq!(p.veto(&g, &g.signers[3], 1_000), Err(OracleError::VetoClosed.into()));
+        assert_eq!(p.veto(&g, &g.signers[3], 999), Ok(true));
+        assert_eq!(p.veto_count(), 3);
+    }
+
+    #[test]
+    fn instant_actions_cannot_be_vetoed() {
+        let g = governance(3, 2);
+        let mut p = proposal(&g, AdminAction::SetPaused { paused: true });
+        assert_eq!(p.veto(&g, &g.signers[1], 0), Err(OracleError::NotVetoable.into()));
+    }
+
+    #[test]
+    fn timelocked_actions() {
+        let instant = [
+            AdminAction::SetPaused { paused: true },
+            AdminAction::SetAssetStatus { id: 1, status: AssetStatus::Halted },
+            AdminAction::ResumeAsset { id: 1 },
+            AdminAction::CancelAuthorityTransfer,
+        ];
+        for action in &instant {
+            assert!(!action.is_timelocked(), "{action:?}");
+        }
+        let timelocked = [
+            AdminAction::ProposeAuthority { new_authority: Pubkey::new_unique() },
+            AdminAction::SetTimelock { timelock_secs: 0 },
+            AdminAction::RetireAsset { id: 1 },
+            AdminAction::CloseState,
+            AdminAction::SetGovernance { signers: keys(2), threshold: 1 },
+        ];
+        for action in &timelocked {
+            assert!(action.is_timelocked(), "{action:?}");
+        }
+    }
+}
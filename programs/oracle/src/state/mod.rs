@@ -0,0 +1,28 @@
+pub mod circuit_breaker;
+pub mod governance;
+pub mod oracle_state;
+pub mod price_history;
+pub mod pyth_mirror;
+pub mod round;
+pub mod timelock;
+pub mod triplet;
+
+pub use circuit_breaker::*;
+pub use governance::*;
+pub use oracle_state::*;
+pub use price_history::*;
+pub use pyth_mirror::*;
+pub use round::*;
+pub use timelock::*;
+pub use triplet::*;
+
+/// Clock at cluster time `unix_timestamp`, with the slot number set to the
+/// same value.
+#[cfg(test)]
+pub(crate) fn test_clock(unix_timestamp: i64) -> anchor_lang::prelude::Clock {
+    anchor_lang::prelude::Clock {
+        slot: unix_timestamp as u64,
+        unix_timestamp,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,397 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use super::{AdminAction, CircuitBreaker, Round, Triplet};
+use std::cell::{Ref, RefMut};
+use std::ops::{Deref, DerefMut};
+
+/// Assets seeded by `initialize`. Ids are stable; anything beyond these is
+/// registered at runtime with `add_asset`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Asset {
+    Btc = 1,
+    Eth = 2,
+    Sol = 3,
+    Hype = 4,
+    Zec = 5,
+    Tsla = 6,
+    Nvda = 7,
+    Mstr = 8,
+    Gold = 9,
+    Silver = 10,
+}
+
+impl Asset {
+    /// Default assets, in id order.
+    pub const ALL: [Asset; 10] = [
+        Asset::Btc,
+        Asset::Eth,
+        Asset::Sol,
+        Asset::Hype,
+        Asset::Zec,
+        Asset::Tsla,
+        Asset::Nvda,
+        Asset::Mstr,
+        Asset::Gold,
+        Asset::Silver,
+    ];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Asset::Btc => "BTC",
+            Asset::Eth => "ETH",
+            Asset::Sol => "SOL",
+            Asset::Hype => "HYPE",
+            Asset::Zec => "ZEC",
+            Asset::Tsla => "TSLA",
+            Asset::Nvda => "NVDA",
+            Asset::Mstr => "MSTR",
+            Asset::Gold => "GOLD",
+            Asset::Silver => "SILVER",
+        }
+    }
+}
+
+impl From<Asset> for u8 {
+    fn from(asset: Asset) -> u8 {
+        asset as u8
+    }
+}
+
+/// Global oracle state (`state_v2` PDA).
+///
+/// Zero-copy header; the asset table follows it in the same account as
+/// `asset_count` packed `AssetEntry` rows starting at `State::HEADER_END`.
+/// Use `StateAccount` to get at both.
+#[account(zero_copy)]
+pub struct State {
+    pub update_authority: Pubkey,  // 32
+    pub pending_authority: Pubkey, // 32, proposed by the admin, default when none
+    pub updaters: [Pubkey; 4],     // 128, slot i is written by updaters[i - 1]
+    pub stale_after_secs: u32,     // 4
+    pub max_clock_drift_ms: u32,   // 4
+    pub asset_count: u32,          // 4
+    pub ema_period_secs: u32,      // 4, time constant of the per-asset EMAs
+    pub queued_count: u64,         // 8, id of the next `QueuedAction`
+    pub last_seqs: [u64; 4],       // 32, last sequence number used by each updater (0 = none)
+    pub timelock_secs: u32,        // 4, delay before a timelocked admin action may run
+    pub bump: u8,                  // 1
+    pub min_quorum: u8,            // 1
+    pub paused: u8,                // 1, 1 = global emergency stop: no updates, no reads
+    pub _padding: [u8; 1],         // 1
+}
+
+impl State {
+    pub const SEED: &'static [u8] = b"state_v2";
+    pub const HEADER_SIZE: usize = std::mem::size_of::<State>(); // 256
+    /// Offset of the first `AssetEntry` in the account data
+    pub const HEADER_END: usize = 8 + Self::HEADER_SIZE;
+
+    /// Account size (including discriminator) for a table of `asset_count` assets.
+    pub fn space(asset_count: usize) -> usize {
+        Self::HEADER_END + asset_count * AssetEntry::SIZE
+    }
+
+    /// Updater registered for slot `index`; `Pubkey::default()` when the slot is empty.
+    pub fn updater(&self, index: u8) -> Result<Pubkey> {
+        require!((1..=4).contains(&index), OracleError::BadIndex);
+        Ok(self.updaters[index as usize - 1])
+    }
+
+    /// Replace the updater for slot `index`, returning the previous key. A key
+    /// may only hold one slot so a single relay cannot outvote the others.
+    pub fn set_updater(&mut self, index: u8, new_updater: Pubkey) -> Result<Pubkey> {
+        let old_updater = self.updater(index)?;
+        if new_updater != Pubkey::default() {
+            require!(!self.updaters.contains(&new_updater), OracleError::DuplicateUpdater);
+        }
+        self.updaters[index as usize - 1] = new_updater;
+        self.last_seqs[index as usize - 1] = 0;
+        Ok(old_updater)
+    }
+
+    /// Consume sequence number `seq` of updater `index` (already validated),
+    /// which must be above the last one it used. `None` skips the check for
+    /// relays that do not number their transactions.
+    pub fn advance_seq(&mut self, index: u8, seq: Option<u64>) -> Result<()> {
+        if let Some(seq) = seq {
+            let last = &mut self.last_seqs[index as usize - 1];
+            require!(seq > *last, OracleError::StaleWrite);
+            *last = seq;
+        }
+        Ok(())
+    }
+
+    /// Reject relay timestamps further than `max_clock_drift_ms` from the cluster clock.
+    pub fn check_client_ts(&self, client_ts_ms: i64, clock: &Clock) -> Result<()> {
+        let drift = client_ts_ms.abs_diff(clock.unix_timestamp.saturating_mul(1000));
+        require!(drift <= self.max_clock_drift_ms as u64, OracleError::ClockDrift);
+        Ok(())
+    }
+
+    /// Whether `signer` may apply `action` directly: it must be the admin,
+    /// and timelocked actions have to be queued once a timelock is set.
+    pub fn authorize(&self, signer: &Pubkey, action: &AdminAction) -> Result<()> {
+        require_keys_eq!(*signer, self.update_authority, OracleError::Unauthorized);
+        require!(self.timelock_secs == 0 || !action.is_timelocked(), OracleError::TimelockRequired);
+        Ok(())
+    }
+}
+
+// AssetEntry rows are cast in place, so they must start 8-byte aligned
+const _: () = assert!(State::HEADER_END % 8 == 0);
+
+/// Largest supported per-asset decimals; keeps 10^decimals within i64
+pub const MAX_DECIMALS: u8 = 18;
+
+/// Trading status of an asset, stored in `AssetEntry::status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssetStatus {
+    Active = 0,
+    /// Neither updated nor served to consumers until an admin resumes the asset
+    Halted = 1,
+    /// Still updated and served, for consumers to close positions against;
+    /// consumers should not open new ones
+    SettlementOnly = 2,
+}
+
+/// One row of the asset table
+#[zero_copy]
+pub struct AssetEntry {
+    pub id: u8,                  // 1
+    pub symbol: [u8; 16],        // 16, zero padded
+    pub decimals: u8,            // 1, prices are stored as value * 10^decimals
+    pub has_history: u8,         // 1, 1 when a `PriceHistory` account is attached
+    pub status: u8,              // 1, `AssetStatus`
+    pub outlier_bps: u16,        // 2, max distance of a slot from the median of the others (0 = off)
+    pub has_pyth_mirror: u8,     // 1, 1 when a `PriceUpdateV2` mirror is attached
+    pub pyth_mirror_bump: u8,    // 1, bump of that mirror's PDA
+    pub breaker: CircuitBreaker, // 32
+    pub prices: Triplet,         // 192
+    pub round: Round,            // 104
+}
+
+impl AssetEntry {
+    pub const SIZE: usize = std::mem::size_of::<AssetEntry>(); // 352
+
+    pub fn new(id: u8, symbol: &str, decimals: u8) -> Result<Self> {
+        require!(!symbol.is_empty() && symbol.len() <= 16, OracleError::BadSymbol);
+        require!(decimals <= MAX_DECIMALS, OracleError::BadDecimals);
+        let mut symbol_bytes = [0u8; 16];
+        symbol_bytes[..symbol.len()].copy_from_slice(symbol.as_bytes());
+        Ok(Self {
+            id,
+            symbol: symbol_bytes,
+            decimals,
+            has_history: 0,
+            status: AssetStatus::Active as u8,
+            outlier_bps: 0,
+            has_pyth_mirror: 0,
+            pyth_mirror_bump: 0,
+            breaker: CircuitBreaker::default(),
+            prices: Triplet::default(),
+            round: Round::default(),
+        })
+    }
+
+    /// Stored status; unknown values read as `Halted`.
+    pub fn status(&self) -> AssetStatus {
+        match self.status {
+            0 => AssetStatus::Active,
+            2 => AssetStatus::SettlementOnly,
+            _ => AssetStatus::Halted,
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.status() == AssetStatus::Halted
+    }
+
+    pub fn symbol_as_string(&self) -> String {
+        String::from_utf8_lossy(
+            &self.symbol[..self.symbol.iter().position(|&b| b == 0).unwrap_or(16)]
+        ).to_string()
+    }
+}
+
+/// Typed view over the raw bytes of the state account: the `State` header
+/// followed by its asset table. Works on borrowed account data on-chain and
+/// on any fetched byte buffer off-chain.
+pub struct StateAccount<D> {
+    data: D,
+}
+
+impl<'a> StateAccount<Ref<'a, [u8]>> {
+    pub fn load(info: &'a AccountInfo) -> Result<Self> {
+        Self::new(Ref::map(info.try_borrow_data()?, |d| &**d))
+    }
+}
+
+impl<'a> StateAccount<RefMut<'a, [u8]>> {
+    pub fn load_mut(info: &'a AccountInfo) -> Result<Self> {
+        Self::new(RefMut::map(info.try_borrow_mut_data()?, |d| &mut **d))
+    }
+}
+
+impl<D: Deref<Target = [u8]>> StateAccount<D> {
+    /// Wrap account data (discriminator included), checking the discriminator
+    /// and that the data is large enough for the advertised asset table.
+    pub fn new(data: D) -> Result<Self> {
+        require!(data.len() >= State::HEADER_END, OracleError::BadStateAccount);
+        require!(data[..8] == *State::DISCRIMINATOR, OracleError::BadStateAccount);
+        let account = Self { data };
+        require!(
+            account.data.len() >= State::space(account.header().asset_count as usize),
+            OracleError::BadStateAccount
+        );
+        Ok(account)
+    }
+
+    pub fn header(&self) -> &State {
+        bytemuck::from_bytes(&self.data[8..State::HEADER_END])
+    }
+
+    pub fn assets(&self) -> &[AssetEntry] {
+        let end = State::space(self.header().asset_count as usize);
+        bytemuck::cast_slice(&self.data[State::HEADER_END..end])
+    }
+
+    pub fn asset(&self, id: u8) -> Result<&AssetEntry> {
+        self.assets()
+            .iter()
+            .find(|a| a.id == id)
+            .ok_or_else(|| error!(OracleError::BadAsset))
+    }
+}
+
+impl<D: DerefMut<Target = [u8]>> StateAccount<D> {
+    pub fn header_mut(&mut self) -> &mut State {
+        bytemuck::from_bytes_mut(&mut self.data[8..State::HEADER_END])
+    }
+
+    pub fn assets_mut(&mut self) -> &mut [AssetEntry] {
+        let end = State::space(self.header().asset_count as usize);
+        bytemuck::cast_slice_mut(&mut self.data[State::HEADER_END..end])
+    }
+
+    pub fn asset_mut(&mut self, id: u8) -> Result<&mut AssetEntry> {
+        self.assets_mut()
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| error!(OracleError::BadAsset))
+    }
+
+    /// Remove asset `id` from the table, shifting the rows after it down, and
+    /// return it. Refused while a history account or Pyth mirror still
+    /// points at it. The caller shrinks the account to the new
+    /// `State::space`.
+    pub fn remove_asset(&mut self, id: u8) -> Result<AssetEntry> {
+        let assets = self.assets_mut();
+        let pos = assets
+            .iter()
+            .position(|a| a.id == id)
+            .ok_or_else(|| error!(OracleError::BadAsset))?;
+        let entry = assets[pos];
+        require!(entry.has_history == 0, OracleError::HistoryEnabled);
+        require!(entry.has_pyth_mirror == 0, OracleError::PythMirrorEnabled);
+        assets.copy_within(pos + 1.., pos);
+        self.header_mut().asset_count -= 1;
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 8-byte aligned account data holding a table of `ids`, with room for
+    /// `extra` more rows.
+    fn state_data(ids: &[u8], extra: usize) -> Vec<u64> {
+        let mut buf = vec![0u64; State::space(ids.len() + extra) / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut buf);
+        data[..8].copy_from_slice(State::DISCRIMINATOR);
+        bytemuck::from_bytes_mut::<State>(&mut data[8..State::HEADER_END]).asset_count = ids.len() as u32;
+        let rows: &mut [AssetEntry] = bytemuck::cast_slice_mut(&mut data[State::HEADER_END..]);
+        for (row, &id) in rows.iter_mut().zip(ids) {
+            row.id = id;
+            row.decimals = id;
+        }
+        buf
+    }
+
+    fn ids<D: Deref<Target = [u8]>>(state: &StateAccount<D>) -> Vec<u8> {
+        state.assets().iter().map(|a| a.id).collect()
+    }
+
+    #[test]
+    fn new_checks_the_table_fits() {
+        let mut buf = state_data(&[1, 2, 3], 0);
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut buf);
+        assert_eq!(data.len(), State::space(3));
+        assert_eq!(ids(&StateAccount::new(&*data).unwrap()), [1, 2, 3]);
+
+        // One row short of what asset_count advertises
+        let short = &data[..State::space(3) - 8];
+        assert_eq!(StateAccount::new(short).err(), Some(OracleError::BadStateAccount.into()));
+        // Too short for the header
+        let header = &data[..State::HEADER_END - 8];
+        assert_eq!(StateAccount::new(header).err(), Some(OracleError::BadStateAccount.into()));
+        // Some other account type
+        data[0] ^= 1;
+        assert_eq!(StateAccount::new(&*data).err(), Some(OracleError::BadStateAccount.into()));
+    }
+
+    #[test]
+    fn new_ignores_trailing_space() {
+        let buf = state_data(&[1, 2], 1);
+        let state = StateAccount::new(bytemuck::cast_slice::<u64, u8>(&buf)).unwrap();
+        assert_eq!(ids(&state), [1, 2]);
+    }
+
+    #[test]
+    fn remove_asset_shifts_the_rows_after_it() {
+        for (id, rest) in [(1, [2, 3, 4]), (3, [1, 2, 4]), (4, [1, 2, 3])] {
+            let mut buf = state_data(&[1, 2, 3, 4], 0);
+            let mut state = StateAccount::new(bytemuck::cast_slice_mut::<u64, u8>(&mut buf)).unwrap();
+            assert_eq!(state.remove_asset(id).unwrap().id, id);
+            assert_eq!(state.header().asset_count, 3);
+            assert_eq!(ids(&state), rest);
+            // Rows moved whole, not just their ids
+            assert!(state.assets().iter().all(|a| a.decimals == a.id));
+
+            // The table still loads once the account is shrunk to the new space
+            let data: &[u8] = bytemuck::cast_slice(&buf);
+            assert_eq!(State::space(3), data.len() - AssetEntry::SIZE);
+            assert_eq!(ids(&StateAccount::new(&data[..State::space(3)]).unwrap()), rest);
+        }
+    }
+
+    #[test]
+    fn remove_asset_refuses_attached_or_unknown_assets() {
+        let mut buf = state_data(&[1, 2], 0);
+        let mut state = StateAccount::new(bytemuck::cast_slice_mut::<u64, u8>(&mut buf)).unwrap();
+        state.asset_mut(1).unwrap().has_history = 1;
+        state.asset_mut(2).unwrap().has_pyth_mirror = 1;
+        assert_eq!(state.remove_asset(1).err(), Some(OracleError::HistoryEnabled.into()));
+        assert_eq!(state.remove_asset(2).err(), Some(OracleError::PythMirrorEnabled.into()));
+        assert_eq!(state.remove_asset(3).err(), Some(OracleError::BadAsset.into()));
+        assert_eq!((state.header().asset_count, ids(&state)), (2, vec![1, 2]));
+    }
+
+    #[test]
+    fn authorize_admin_actions() {
+        let admin = Pubkey::new_unique();
+        let mut s: State = bytemuck::Zeroable::zeroed();
+        s.update_authority = admin;
+        let pause = AdminAction::SetPaused { paused: true };
+        let retire = AdminAction::RetireAsset { id: 1 };
+
+        assert_eq!(s.authorize(&admin, &retire), Ok(()));
+        assert_eq!(s.authorize(&Pubkey::new_unique(), &pause), Err(OracleError::Unauthorized.into()));
+
+        // With a timelock only the instant actions may skip the queue
+        s.timelock_secs = 60;
+        assert_eq!(s.authorize(&admin, &pause), Ok(()));
+        assert_eq!(s.authorize(&admin, &retire), Err(OracleError::TimelockRequired.into()));
+        assert_eq!(s.authorize(&Pubkey::new_unique(), &retire), Err(OracleError::Unauthorized.into()));
+    }
+}
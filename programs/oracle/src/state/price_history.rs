@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+/// Number of aggregates kept per asset
+pub const HISTORY_LEN: usize = 64;
+
+/// One aggregated price, as recorded in `PriceHistory`
+#[zero_copy]
+#[derive(Default)]
+pub struct HistoryEntry {
+    pub price: i64,   // 8, aggregated price in the asset's decimals
+    pub conf: u64,    // 8, median absolute deviation at aggregation
+    pub slot: u64,    // 8, cluster slot of the aggregation
+    pub unix_ts: i64, // 8, cluster unix_timestamp of the aggregation
+}
+
+/// Ring buffer of the last `HISTORY_LEN` aggregates of one asset
+/// (`[b"history", &[asset]]` PDA). Optional; created with `init_history`
+/// and appended to by every update that re-aggregates the asset.
+#[account(zero_copy)]
+pub struct PriceHistory {
+    pub head: u32,                            // 4, index the next entry is written to
+    pub len: u32,                             // 4, number of valid entries
+    pub asset: u8,                            // 1
+    pub bump: u8,                             // 1
+    pub _padding: [u8; 6],                    // 6
+    pub entries: [HistoryEntry; HISTORY_LEN], // 2048
+}
+
+impl PriceHistory {
+    pub const SEED: &'static [u8] = b"history";
+    pub const SIZE: usize = std::mem::size_of::<PriceHistory>(); // 2064
+
+    /// Append an entry, overwriting the oldest once the buffer is full.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.entries[self.head as usize] = entry;
+        self.head = (self.head + 1) % HISTORY_LEN as u32;
+        self.len = (self.len + 1).min(HISTORY_LEN as u32);
+    }
+
+    /// Most recent entry, if any.
+    pub fn latest(&self) -> Option<&HistoryEntry> {
+        self.iter().next()
+    }
+
+    /// Valid entries, newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryEntry> {
+        let head = self.head as usize + HISTORY_LEN;
+        (1..=self.len as usize).map(move |i| &self.entries[(head - i) % HISTORY_LEN])
+    }
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use super::Triplet;
+
+/// How thoroughly a Pyth update was verified; mirrors report `Full` while served
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VerificationLevel {
+    Partial { num_signatures: u8 },
+    Full,
+}
+
+/// Field-for-field copy of Pyth's `PriceFeedMessage`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PriceFeedMessage {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+}
+
+/// Mirror of one asset's aggregate (`[b"pyth_mirror", &[asset]]` PDA) laid out
+/// like Pyth's `PriceUpdateV2`, discriminator included, so programs reading
+/// that layout can consume it as is. Programs that also pin the owner to the
+/// Pyth receiver program have to accept this program as the owner instead.
+/// Optional; created with `init_pyth_mirror` and refreshed by every update
+/// that re-aggregates the asset. It reports `Partial` verification once the
+/// breaker halts the asset, or once it is synced (with the status change or
+/// through `sync_pyth_mirrors`) after a pause or halt, so readers insisting
+/// on `Full` (as Pyth's `get_price_no_older_than` does) stop accepting it.
+#[account]
+pub struct PriceUpdateV2 {
+    pub write_authority: Pubkey,                // 32, the `state_v2` PDA
+    pub verification_level: VerificationLevel, // 2 reserved, 1 used by `Full`
+    pub price_message: PriceFeedMessage,       // 84
+    pub posted_slot: u64,                      // 8, cluster slot of the last refresh
+}
+
+impl PriceUpdateV2 {
+    pub const SEED: &'static [u8] = b"pyth_mirror";
+    pub const SIZE: usize = 32 + 2 + 84 + 8; // same as Pyth's, discriminator aside
+
+    /// Level reported while the asset cannot be served (oracle paused or
+    /// asset halted); readers requiring `Full` verification refuse it.
+    pub const UNSERVABLE: VerificationLevel = VerificationLevel::Partial { num_signatures: 0 };
+
+    /// Copy the aggregate and EMAs of `prices` into the message and mark it
+    /// `Full`; only called while the asset is served.
+    pub fn mirror(&mut self, prices: &Triplet, decimals: u8, slot: u64) {
+        self.verification_level = VerificationLevel::Full;
+        let m = &mut self.price_message;
+        if prices.agg_ts != m.publish_time {
+            m.prev_publish_time = m.publish_time;
+        }
+        m.price = prices.agg_price;
+        m.conf = prices.agg_conf;
+        m.exponent = -(decimals as i32);
+        m.publish_time = prices.agg_ts;
+        m.ema_price = prices.ema_price;
+        m.ema_conf = prices.ema_conf;
+        self.posted_slot = slot;
+    }
+}
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use super::circuit_breaker::exceeds;
+use super::triplet::median;
+
+/// Round view of one asset's updates, Chainlink style. Submissions collect in
+/// the open round until `min_answers` of them lie within `tolerance_bps` of
+/// each other; their median becomes the round's answer and the next
+/// submission opens a new round. A round still open after the staleness
+/// window is abandoned for a new one.
+#[zero_copy]
+#[derive(Default)]
+pub struct Round {
+    pub round_id: u64,          // 8, latest round opened (0 = none yet)
+    pub open_slot: u64,         // 8, cluster slot the latest round opened
+    pub started_at: i64,        // 8, cluster unix_timestamp the latest round opened
+    pub submissions: [i64; 4],  // 32, price per updater in the latest round
+    pub answer: i64,            // 8, answer of round `answered_in_round`
+    pub answered_in_round: u64, // 8, latest finalized round (0 = none yet)
+    pub answer_started_at: i64, // 8, cluster unix_timestamp that round opened
+    pub updated_at: i64,        // 8, cluster unix_timestamp that round finalized
+    pub close_slot: u64,        // 8, cluster slot that round finalized
+    pub tolerance_bps: u16,     // 2, max spread of the agreeing submissions
+    pub min_answers: u8,        // 1, submissions needed to finalize (0 = rounds off)
+    pub submitted: u8,          // 1, bit `index - 1` set per updater in the latest round
+    pub _padding: [u8; 4],      // 4
+}
+
+impl Round {
+    pub const SIZE: usize = std::mem::size_of::<Round>(); // 104
+
+    /// Whether the latest round is still waiting for agreement.
+    pub fn is_open(&self) -> bool {
+        self.round_id > self.answered_in_round
+    }
+
+    /// Record `price` from updater `index` (already validated to be 1..=4) in
+    /// the open round, first opening one when there is none or the open one
+    /// started more than `timeout_secs` ago. Returns whether this submission
+    /// finalized the round.
+    pub fn submit(&mut self, index: u8, price: i64, clock: &Clock, timeout_secs: u32) -> bool {
+        if self.min_answers == 0 {
+            return false;
+        }
+        let now = clock.unix_timestamp;
+        if !self.is_open() || now.saturating_sub(self.started_at) > timeout_secs as i64 {
+            self.round_id += 1;
+            self.open_slot = clock.slot;
+            self.started_at = now;
+            self.submissions = [0; 4];
+            self.submitted = 0;
+        }
+        let i = index as usize - 1;
+        self.submissions[i] = price;
+        self.submitted |= 1 << i;
+
+        let Some(answer) = self.agreement() else {
+            return false;
+        };
+        self.answer = answer;
+        self.answered_in_round = self.round_id;
+        self.answer_started_at = self.started_at;
+        self.updated_at = now;
+        self.close_slot = clock.slot;
+        true
+    }
+
+    /// Drop updater `index`'s submission (already validated to be 1..=4) from
+    /// the open round.
+    pub fn withdraw(&mut self, index: u8) {
+        let i = index as usize - 1;
+        self.submissions[i] = 0;
+        self.submitted &= !(1 << i);
+    }
+
+    /// Median of the lowest `min_answers` submissions, in price order, that
+    /// lie within `tolerance_bps` of each other, if any.
+    fn agreement(&self) -> Option<i64> {
+        let k = self.min_answers as usize;
+        let mut values = [0i64; 4];
+        let mut count = 0;
+        for (i, &price) in self.submissions.iter().enumerate() {
+            if self.submitted & (1 << i) != 0 {
+                values[count] = price;
+                count += 1;
+            }
+        }
+        if count < k {
+            return None;
+        }
+        let values = &mut values[..count];
+        values.sort_unstable();
+        values
+            .windows(k)
+            .find(|w| !exceeds(w[k - 1], w[0], self.tolerance_bps))
+            .map(median)
+    }
+
+    /// Convert the submissions and the answer from `from` to `to` decimals,
+    /// like `Triplet::rescale`.
+    pub fn rescale(&mut self, from: u8, to: u8) -> Result<()> {
+        let factor = 10i128.pow(from.abs_diff(to) as u32);
+        let scale = |v: i128| if to >= from { v * factor } else { v / factor };
+        let to_i64 = |v: i64| i64::try_from(scale(v as i128)).map_err(|_| error!(OracleError::RescaleOverflow));
+
+        for submission in self.submissions.iter_mut() {
+            *submission = to_i64(*submission)?;
+        }
+        self.answer = to_i64(self.answer)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::test_clock as clock;
+
+    fn round(min_answers: u8, tolerance_bps: u16) -> Round {
+        Round {
+            min_answers,
+            tolerance_bps,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finalizes_at_k_agreeing_submissions() {
+        let mut r = round(3, 100);
+        assert!(!r.submit(1, 10_000, &clock(100), 60));
+        assert!(!r.submit(2, 10_050, &clock(101), 60));
+        assert!(r.is_open());
+        assert!(r.submit(3, 10_020, &clock(102), 60));
+
+        assert!(!r.is_open());
+        assert_eq!((r.round_id, r.answered_in_round, r.answer), (1, 1, 10_020));
+        assert_eq!((r.answer_started_at, r.updated_at, r.close_slot), (100, 102, 102));
+
+        // The next submission opens round 2
+        assert!(!r.submit(1, 10_030, &clock(103), 60));
+        assert_eq!((r.round_id, r.answered_in_round, r.submitted), (2, 1, 0b1));
+    }
+
+    #[test]
+    fn submissions_outside_tolerance_do_not_finalize() {
+        let mut r = round(3, 100);
+        assert!(!r.submit(1, 10_000, &clock(100), 60));
+        assert!(!r.submit(2, 10_500, &clock(101), 60));
+        assert!(!r.submit(3, 11_000, &clock(102), 60));
+        assert!(r.is_open());
+        assert_eq!(r.answered_in_round, 0);
+    }
+
+    #[test]
+    fn finalizes_on_the_agreeing_subset() {
+        let mut r = round(2, 100);
+        assert!(!r.submit(1, 10_000, &clock(100), 60));
+        assert!(!r.submit(2, 12_000, &clock(101), 60));
+        assert!(r.submit(3, 10_050, &clock(102), 60));
+        assert_eq!(r.answer, 10_025);
+    }
+
+    #[test]
+    fn timed_out_round_is_abandoned() {
+        let mut r = round(2, 100);
+        assert!(!r.submit(1, 10_000, &clock(100), 60));
+        // Too late to join round 1: a new round opens with this submission alone
+        assert!(!r.submit(2, 10_000, &clock(161), 60));
+        assert_eq!((r.round_id, r.started_at, r.submitted), (2, 161, 0b10));
+        assert!(r.answered_in_round < r.round_id);
+        assert_eq!(r.answered_in_round, 0);
+    }
+
+    #[test]
+    fn zero_min_answers_turns_rounds_off() {
+        let mut r = round(0, 100);
+        assert!(!r.submit(1, 10_000, &clock(100), 60));
+        assert!(!r.submit(2, 10_000, &clock(101), 60));
+        assert_eq!((r.round_id, r.answered_in_round, r.submitted), (0, 0, 0));
+    }
+
+    #[test]
+    fn withdrawn_submission_no_longer_counts() {
+        let mut r = round(2, 100);
+        assert!(!r.submit(1, 10_000, &clock(100), 60));
+        r.withdraw(1);
+        assert!(!r.submit(2, 10_000, &clock(101), 60));
+        assert!(r.is_open());
+    }
+
+    #[test]
+    fn rescale_scales_submissions_and_answer() {
+        let mut r = Round {
+            submissions: [12_345_678, 0, 12_345_699, 1],
+            answer: 12_345_688,
+            ..Default::default()
+        };
+        r.rescale(6, 8).unwrap();
+        assert_eq!(r.submissions, [1_234_567_800, 0, 1_234_569_900, 100]);
+        assert_eq!(r.answer, 1_234_568_800);
+
+        r.rescale(8, 5).unwrap();
+        assert_eq!(r.submissions, [1_234_567, 0, 1_234_569, 0]);
+        assert_eq!(r.answer, 1_234_568);
+    }
+
+    #[test]
+    fn rescale_up_overflow_fails() {
+        let mut r = Round {
+            answer: i64::MAX / 100 + 1,
+            ..Default::default()
+        };
+        assert_eq!(r.rescale(0, 2), Err(OracleError::RescaleOverflow.into()));
+    }
+}
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+use super::AdminAction;
+
+/// Longest configurable timelock
+pub const MAX_TIMELOCK_SECS: u32 = 30 * 24 * 60 * 60;
+
+/// Admin action waiting out the timelock (`[b"queued", id]` PDA), closed to
+/// the admin when executed or cancelled.
+#[account]
+pub struct QueuedAction {
+    pub id: u64,             // 8
+    pub eta: i64,            // 8, cluster unix_timestamp from which it may run
+    pub bump: u8,            // 1
+    pub action: AdminAction, // up to AdminAction::MAX_SIZE
+}
+
+impl QueuedAction {
+    pub const SEED: &'static [u8] = b"queued";
+    pub const SIZE: usize = 8 + 8 + 1 + AdminAction::MAX_SIZE;
+}
@@ -0,0 +1,514 @@
+use anchor_lang::prelude::*;
+use crate::errors::OracleError;
+use super::circuit_breaker::exceeds;
+
+/// Prices from the 4 updater slots of one asset plus their aggregate
+#[zero_copy]
+#[derive(Default)]
+pub struct Triplet {
+    pub param1: i64,
+    pub param2: i64,
+    pub param3: i64,
+    pub param4: i64,
+    pub ts1: i64,
+    pub ts2: i64,
+    pub ts3: i64,
+    pub ts4: i64,
+    pub slot1: u64,      // cluster slot of the last write, per updater
+    pub slot2: u64,
+    pub slot3: u64,
+    pub slot4: u64,
+    pub unix_ts1: i64,   // cluster unix_timestamp of the last write, per updater
+    pub unix_ts2: i64,
+    pub unix_ts3: i64,
+    pub unix_ts4: i64,
+    pub agg_price: i64,  // median of the fresh slots at the last successful aggregation
+    pub agg_ts: i64,     // cluster unix_timestamp of the last successful aggregation
+    pub agg_conf: u64,   // median absolute deviation of the contributing slots
+    pub agg_spread: i64, // max - min of the contributing slots
+    pub cum_price: i64,  // wrapping sum of agg_price * seconds held, as of agg_ts
+    pub ema_price: i64,  // time-weighted EMA of agg_price, as of agg_ts
+    pub ema_conf: u64,   // time-weighted EMA of agg_conf, as of agg_ts
+    pub agg_count: u8,   // number of contributing slots (0 = never aggregated)
+    pub excluded: u8,    // bit `index - 1` set for each slot rejected as an outlier
+    pub _padding: [u8; 6],
+}
+/// Aggregate of an asset's fresh slots, before it is stored in the `Triplet`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Aggregate {
+    pub price: i64,    // median of the remaining slots
+    pub conf: u64,     // median absolute deviation of the remaining slots
+    pub spread: i64,   // max - min of the remaining slots
+    pub count: u8,     // number of remaining slots
+    pub excluded: u8,  // bit `index - 1` set for each slot rejected as an outlier
+}
+
+impl Triplet {
+    pub const SIZE: usize = std::mem::size_of::<Triplet>(); // 192
+
+    /// Write `price` into updater slot `index` (already validated to be 1..=4),
+    /// stamping it with the cluster slot and unix_timestamp.
+    pub fn write(&mut self, index: u8, price: i64, client_ts_ms: i64, clock: &Clock) {
+        let (slot, now) = (clock.slot, clock.unix_timestamp);
+        match index {
+            1 => { self.param1 = price; self.ts1 = client_ts_ms; self.slot1 = slot; self.unix_ts1 = now; }
+            2 => { self.param2 = price; self.ts2 = client_ts_ms; self.slot2 = slot; self.unix_ts2 = now; }
+            3 => { self.param3 = price; self.ts3 = client_ts_ms; self.slot3 = slot; self.unix_ts3 = now; }
+            4 => { self.param4 = price; self.ts4 = client_ts_ms; self.slot4 = slot; self.unix_ts4 = now; }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Forget the last write to slot `index` (already validated to be 1..=4),
+    /// so it stops counting toward the aggregate. If that write went into the
+    /// current aggregate, the aggregate is dropped as well (`agg_count = 0`)
+    /// and reads fail with `StalePrice` until the remaining slots aggregate
+    /// again. That next aggregation starts the EMAs afresh.
+    pub fn clear(&mut self, index: u8, stale_after_secs: u32) {
+        let (_, unix_ts) = self.slots()[index as usize - 1];
+        let contributed = self.agg_count > 0
+            && unix_ts > 0
+            && unix_ts <= self.agg_ts
+            && self.agg_ts - unix_ts <= stale_after_secs as i64
+            && self.excluded & (1 << (index - 1)) == 0;
+        if contributed {
+            self.agg_count = 0;
+        }
+        match index {
+            1 => { self.param1 = 0; self.ts1 = 0; self.slot1 = 0; self.unix_ts1 = 0; }
+            2 => { self.param2 = 0; self.ts2 = 0; self.slot2 = 0; self.unix_ts2 = 0; }
+            3 => { self.param3 = 0; self.ts3 = 0; self.slot3 = 0; self.unix_ts3 = 0; }
+            4 => { self.param4 = 0; self.ts4 = 0; self.slot4 = 0; self.unix_ts4 = 0; }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Client timestamp of the last write to slot `index` (already validated to be 1..=4).
+    pub fn client_ts(&self, index: u8) -> i64 {
+        match index {
+            1 => self.ts1,
+            2 => self.ts2,
+            3 => self.ts3,
+            4 => self.ts4,
+            _ => unreachable!(),
+        }
+    }
+
+    /// `(price, unix_ts)` for each updater slot, in index order.
+    pub fn slots(&self) -> [(i64, i64); 4] {
+        [
+            (self.param1, self.unix_ts1),
+            (self.param2, self.unix_ts2),
+            (self.param3, self.unix_ts3),
+            (self.param4, self.unix_ts4),
+        ]
+    }
+
+    /// Recompute the aggregate from the slots written within `stale_after_secs`
+    /// of `now` and store it; see `candidate` and `commit`. Returns `false`
+    /// and keeps the previous aggregate when there is no candidate.
+    pub fn aggregate(
+        &mut self,
+        now: i64,
+        stale_after_secs: u32,
+        min_quorum: u8,
+        ema_period_secs: u32,
+        outlier_bps: u16,
+    ) -> bool {
+        match self.candidate(now, stale_after_secs, min_quorum, outlier_bps) {
+            Some(aggregate) => {
+                self.commit(&aggregate, now, ema_period_secs);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Aggregate of the slots written within `stale_after_secs` of `now`
+    /// (cluster time), leaving out the ones more than `outlier_bps` from the
+    /// median of the others (0 disables that). `None` when fewer than
+    /// `min_quorum` slots remain.
+    pub fn candidate(&self, now: i64, stale_after_secs: u32, min_quorum: u8, outlier_bps: u16) -> Option<Aggregate> {
+        let mut candidates = [(0usize, 0i64); 4];
+        let mut count = 0;
+        for (i, (price, unix_ts)) in self.slots().into_iter().enumerate() {
+            if unix_ts > 0 && now.saturating_sub(unix_ts) <= stale_after_secs as i64 {
+                candidates[count] = (i, price);
+                count += 1;
+            }
+        }
+        let candidates = &candidates[..count];
+        let excluded = outliers(candidates, outlier_bps);
+
+        let mut fresh = [0i64; 4];
+        let mut count = 0;
+        for &(i, price) in candidates {
+            if excluded & (1 << i) == 0 {
+                fresh[count] = price;
+                count += 1;
+            }
+        }
+        if count == 0 || count < min_quorum as usize {
+            return None;
+        }
+
+        let fresh = &mut fresh[..count];
+        fresh.sort_unstable();
+        let price = median(fresh);
+        Some(Aggregate {
+            price,
+            conf: median_abs_deviation(fresh, price),
+            spread: fresh[count - 1].saturating_sub(fresh[0]),
+            count: count as u8,
+            excluded,
+        })
+    }
+
+    /// Store `aggregate` as computed at `now`. The TWAP accumulator is
+    /// advanced by the previous aggregate times the seconds it was held, and
+    /// the EMAs move toward the new values by `elapsed / ema_period_secs`
+    /// (capped at 1).
+    pub fn commit(&mut self, aggregate: &Aggregate, now: i64, ema_period_secs: u32) {
+        let Aggregate { price, conf, .. } = *aggregate;
+        if self.agg_count == 0 {
+            self.ema_price = price;
+            self.ema_conf = conf;
+        } else {
+            let elapsed = now.saturating_sub(self.agg_ts).max(0);
+            self.cum_price = self.cumulative_at(now);
+            let weight = elapsed.min(ema_period_secs as i64) as i128;
+            let period = ema_period_secs as i128;
+            let ema = |old: i128, new: i128| old + (new - old) * weight / period;
+            self.ema_price = ema(self.ema_price as i128, price as i128) as i64;
+            self.ema_conf = ema(self.ema_conf as i128, conf as i128) as u64;
+        }
+        self.agg_price = price;
+        self.agg_conf = conf;
+        self.agg_ts = now;
+        self.agg_spread = aggregate.spread;
+        self.agg_count = aggregate.count;
+        self.excluded = aggregate.excluded;
+    }
+
+    /// TWAP accumulator extrapolated to `now`, assuming `agg_price` has held
+    /// since `agg_ts`. The TWAP between two observations is
+    /// `c1.wrapping_sub(c0) / (t1 - t0)`.
+    pub fn cumulative_at(&self, now: i64) -> i64 {
+        let elapsed = now.saturating_sub(self.agg_ts).max(0);
+        self.cum_price.wrapping_add(self.agg_price.wrapping_mul(elapsed))
+    }
+
+    /// Whether the aggregate exists and was computed within `max_age_secs` of the cluster clock.
+    pub fn is_fresh(&self, clock: &Clock, max_age_secs: u64) -> bool {
+        self.agg_count > 0 && clock.unix_timestamp.saturating_sub(self.agg_ts) as u64 <= max_age_secs
+    }
+
+    /// Aggregated price, rejected with `StalePrice` when older than `max_age_secs`.
+    /// Every read path should go through this rather than `agg_price` directly.
+    pub fn get_price_no_older_than(&self, clock: &Clock, max_age_secs: u64) -> Result<i64> {
+        require!(self.is_fresh(clock, max_age_secs), OracleError::StalePrice);
+        Ok(self.agg_price)
+    }
+
+    /// Convert every stored price from `from` to `to` decimals. Scaling down
+    /// truncates toward zero; scaling up fails with `RescaleOverflow` instead of wrapping.
+    /// `cum_price` wraps and cannot be rescaled, so it is left as is:
+    /// TWAPs must not span a decimals change.
+    pub fn rescale(&mut self, from: u8, to: u8) -> Result<()> {
+        let factor = 10i128.pow(from.abs_diff(to) as u32);
+        let scale = |v: i128| if to >= from { v * factor } else { v / factor };
+        let to_i64 = |v: i64| i64::try_from(scale(v as i128)).map_err(|_| error!(OracleError::RescaleOverflow));
+
+        self.param1 = to_i64(self.param1)?;
+        self.param2 = to_i64(self.param2)?;
+        self.param3 = to_i64(self.param3)?;
+        self.param4 = to_i64(self.param4)?;
+        self.agg_price = to_i64(self.agg_price)?;
+        self.agg_spread = to_i64(self.agg_spread)?;
+        self.ema_price = to_i64(self.ema_price)?;
+        let to_u64 = |v: u64| u64::try_from(scale(v as i128)).map_err(|_| error!(OracleError::RescaleOverflow));
+        self.agg_conf = to_u64(self.agg_conf)?;
+        self.ema_conf = to_u64(self.ema_conf)?;
+        Ok(())
+    }
+}
+
+/// Bitmask (bit `index - 1`) of the `(slot, price)` pairs in `fresh` more than
+/// `outlier_bps` from the median of the others. With an even number of others
+/// anything between the middle pair counts as their median. Needs three slots
+/// or more: between two, there is no telling which one is off.
+fn outliers(fresh: &[(usize, i64)], outlier_bps: u16) -> u8 {
+    if outlier_bps == 0 || fresh.len() < 3 {
+        return 0;
+    }
+    let mut mask = 0;
+    for &(i, price) in fresh {
+        let mut others = [0i64; 3];
+        let mut n = 0;
+        for &(j, other) in fresh {
+            if j != i {
+                others[n] = other;
+                n += 1;
+            }
+        }
+        let others = &mut others[..n];
+        others.sort_unstable();
+        let reference = price.clamp(others[(n - 1) / 2], others[n / 2]);
+        if exceeds(price, reference, outlier_bps) {
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
+
+/// Median of a sorted, non-empty slice; even lengths average the middle pair.
+pub(crate) fn median(sorted: &[i64]) -> i64 {
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        ((sorted[mid - 1] as i128 + sorted[mid] as i128) / 2) as i64
+    }
+}
+
+/// Median absolute deviation of at most four `values` around `center`.
+fn median_abs_deviation(values: &[i64], center: i64) -> u64 {
+    let mut deviations = [0u64; 4];
+    let deviations = &mut deviations[..values.len()];
+    for (d, v) in deviations.iter_mut().zip(values) {
+        *d = v.abs_diff(center);
+    }
+    deviations.sort_unstable();
+
+    let mid = deviations.len() / 2;
+    if deviations.len() % 2 == 1 {
+        deviations[mid]
+    } else {
+        ((deviations[mid - 1] as u128 + deviations[mid] as u128) / 2) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::test_clock as clock;
+
+    /// Write `prices` (index order, 0 = leave the slot alone) at cluster time `now`.
+    fn write_all(t: &mut Triplet, prices: [i64; 4], now: i64) {
+        for (i, price) in prices.into_iter().enumerate() {
+            if price != 0 {
+                t.write(i as u8 + 1, price, now * 1_000, &clock(now));
+            }
+        }
+    }
+
+    #[test]
+    fn quorum_not_met_keeps_previous_aggregate() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [100, 102, 101, 0], 1_000);
+        assert!(t.aggregate(1_000, 60, 3, 600, 0));
+        assert_eq!((t.agg_price, t.agg_ts, t.agg_count), (101, 1_000, 3));
+
+        // The other slots have gone stale, leaving one fresh slot out of three needed
+        write_all(&mut t, [200, 0, 0, 0], 1_100);
+        assert!(!t.aggregate(1_100, 60, 3, 600, 0));
+        assert_eq!((t.agg_price, t.agg_ts, t.agg_count), (101, 1_000, 3));
+    }
+
+    #[test]
+    fn stale_and_unwritten_slots_are_ignored() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [1_000, 0, 0, 0], 1_000);
+        write_all(&mut t, [0, 300, 310, 0], 1_100);
+        // Slot 1 is 100s old with a 60s window, slot 4 was never written
+        let a = t.candidate(1_100, 60, 1, 0).unwrap();
+        assert_eq!(a, Aggregate { price: 305, conf: 5, spread: 10, count: 2, excluded: 0 });
+        // No quorum at all once everything is stale
+        assert_eq!(t.candidate(1_200, 60, 1, 0), None);
+    }
+
+    #[test]
+    fn odd_count_median_and_mad() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [150, 100, 101, 0], 1_000);
+        let a = t.candidate(1_000, 60, 1, 0).unwrap();
+        // Deviations from 101: 49, 1, 0
+        assert_eq!(a, Aggregate { price: 101, conf: 1, spread: 50, count: 3, excluded: 0 });
+    }
+
+    #[test]
+    fn even_count_median_and_mad() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [120, 100, 110, 104], 1_000);
+        let a = t.candidate(1_000, 60, 1, 0).unwrap();
+        // Median of 104 and 110; deviations from 107 sort to 3, 3, 7, 13
+        assert_eq!(a, Aggregate { price: 107, conf: 5, spread: 20, count: 4, excluded: 0 });
+    }
+
+    #[test]
+    fn even_median_does_not_overflow() {
+        assert_eq!(median(&[i64::MAX - 1, i64::MAX]), i64::MAX - 1);
+        assert_eq!(median_abs_deviation(&[0, u32::MAX as i64], 0), u32::MAX as u64 / 2);
+    }
+
+    #[test]
+    fn ema_and_twap_advance() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [100, 100, 100, 0], 1_000);
+        assert!(t.aggregate(1_000, 60, 1, 100, 0));
+        // The first aggregate seeds the EMAs and starts the accumulator
+        assert_eq!((t.ema_price, t.ema_conf, t.cum_price), (100, 0, 0));
+
+        write_all(&mut t, [200, 180, 210, 0], 1_050);
+        assert!(t.aggregate(1_050, 60, 1, 100, 0));
+        // 100 held for 50s; EMAs move half way (50s of a 100s period)
+        assert_eq!(t.cum_price, 100 * 50);
+        assert_eq!((t.ema_price, t.ema_conf), (150, 5));
+        assert_eq!(t.cumulative_at(1_060), 100 * 50 + 200 * 10);
+
+        // Past a full period the EMAs land on the new aggregate
+        write_all(&mut t, [300, 300, 300, 0], 1_300);
+        assert!(t.aggregate(1_300, 60, 1, 100, 0));
+        assert_eq!(t.cum_price, 100 * 50 + 200 * 250);
+        assert_eq!((t.ema_price, t.ema_conf), (300, 0));
+    }
+
+    #[test]
+    fn outliers_need_three_fresh_slots() {
+        assert_eq!(outliers(&[(0, 100), (1, 1_000)], 100), 0);
+        // A stale third slot does not count either
+        let mut t = Triplet::default();
+        write_all(&mut t, [1_000, 0, 0, 0], 1_000);
+        write_all(&mut t, [0, 100, 200, 0], 1_100);
+        let a = t.candidate(1_100, 60, 1, 100).unwrap();
+        assert_eq!((a.count, a.excluded), (2, 0));
+    }
+
+    #[test]
+    fn outliers_off_when_threshold_is_zero() {
+        assert_eq!(outliers(&[(0, 100), (1, 101), (2, 1_000)], 0), 0);
+    }
+
+    #[test]
+    fn one_far_slot_among_three_is_excluded() {
+        assert_eq!(outliers(&[(0, 100), (1, 101), (2, 150)], 500), 0b100);
+
+        let mut t = Triplet::default();
+        write_all(&mut t, [100, 101, 150, 0], 1_000);
+        let a = t.candidate(1_000, 60, 2, 500).unwrap();
+        assert_eq!(a, Aggregate { price: 100, conf: 0, spread: 1, count: 2, excluded: 0b100 });
+    }
+
+    #[test]
+    fn one_far_slot_among_four_is_excluded() {
+        assert_eq!(outliers(&[(0, 100), (1, 101), (2, 102), (3, 200)], 500), 0b1000);
+
+        let mut t = Triplet::default();
+        write_all(&mut t, [200, 101, 100, 102], 1_000);
+        assert!(t.aggregate(1_000, 60, 3, 600, 500));
+        assert_eq!((t.agg_price, t.agg_count, t.excluded), (101, 3, 0b0001));
+    }
+
+    #[test]
+    fn two_against_two_excludes_all_and_keeps_aggregate() {
+        assert_eq!(outliers(&[(0, 100), (1, 100), (2, 200), (3, 200)], 500), 0b1111);
+
+        let mut t = Triplet::default();
+        write_all(&mut t, [100, 100, 101, 0], 1_000);
+        assert!(t.aggregate(1_000, 60, 1, 600, 500));
+        assert_eq!(t.agg_price, 100);
+
+        write_all(&mut t, [0, 0, 200, 200], 1_010);
+        assert_eq!(t.candidate(1_010, 60, 1, 500), None);
+        assert!(!t.aggregate(1_010, 60, 1, 600, 500));
+        assert_eq!((t.agg_price, t.agg_ts, t.excluded), (100, 1_000, 0));
+    }
+
+    #[test]
+    fn clearing_a_contributing_slot_drops_the_aggregate() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [100, 101, 102, 0], 1_000);
+        assert!(t.aggregate(1_000, 60, 1, 600, 0));
+        t.clear(2, 60);
+        assert_eq!((t.param2, t.agg_count), (0, 0));
+        assert!(!t.is_fresh(&clock(1_000), 60));
+
+        // The remaining slots aggregate again on their own
+        assert!(t.aggregate(1_000, 60, 1, 600, 0));
+        assert_eq!((t.agg_price, t.agg_count), (101, 2));
+    }
+
+    #[test]
+    fn clearing_a_slot_outside_the_aggregate_keeps_it() {
+        let mut t = Triplet::default();
+        write_all(&mut t, [100, 101, 150, 0], 1_000);
+        assert!(t.aggregate(1_000, 60, 1, 600, 500));
+        assert_eq!((t.agg_price, t.excluded), (100, 0b100));
+
+        // An excluded outlier, a slot gone stale before the aggregation, one
+        // written after it and one never written did not contribute
+        t.clear(3, 60);
+        t.clear(4, 60);
+        write_all(&mut t, [0, 0, 0, 99], 1_010);
+        t.clear(4, 60);
+        t.unix_ts1 = 900;
+        t.clear(1, 60);
+        assert_eq!((t.agg_price, t.agg_count), (100, 2));
+    }
+
+    fn rescale_sample() -> Triplet {
+        Triplet {
+            param1: 12_345_678,
+            param2: 12_345_699,
+            param3: 0,
+            param4: 12_345_601,
+            agg_price: 12_345_678,
+            agg_spread: 98,
+            agg_conf: 199,
+            ema_price: 12_345_000,
+            ema_conf: 250,
+            cum_price: 7,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rescale_up_scales_prices_and_conf() {
+        let mut t = rescale_sample();
+        t.rescale(6, 8).unwrap();
+        assert_eq!(
+            [t.param1, t.param2, t.param3, t.param4],
+            [1_234_567_800, 1_234_569_900, 0, 1_234_560_100]
+        );
+        assert_eq!((t.agg_price, t.agg_spread, t.ema_price), (1_234_567_800, 9_800, 1_234_500_000));
+        assert_eq!((t.agg_conf, t.ema_conf), (19_900, 25_000));
+        // The wrapping accumulator is left alone
+        assert_eq!(t.cum_price, 7);
+    }
+
+    #[test]
+    fn rescale_down_truncates() {
+        let mut t = rescale_sample();
+        t.rescale(8, 6).unwrap();
+        assert_eq!([t.param1, t.param2, t.param3, t.param4], [123_456, 123_456, 0, 123_456]);
+        assert_eq!((t.agg_price, t.agg_spread, t.ema_price), (123_456, 0, 123_450));
+        assert_eq!((t.agg_conf, t.ema_conf), (1, 2));
+        assert_eq!(t.cum_price, 7);
+    }
+
+    #[test]
+    fn rescale_up_overflow_fails() {
+        let mut t = Triplet {
+            param2: i64::MAX / 10 + 1,
+            ..Default::default()
+        };
+        assert_eq!(t.rescale(0, 1), Err(OracleError::RescaleOverflow.into()));
+
+        let mut t = Triplet {
+            agg_conf: u64::MAX / 100 + 1,
+            ..Default::default()
+        };
+        assert_eq!(t.rescale(6, 8), Err(OracleError::RescaleOverflow.into()));
+        assert_eq!(Triplet::default().rescale(0, crate::state::MAX_DECIMALS), Ok(()));
+    }
+}
@@ -0,0 +1,107 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program, BN } from "@coral-xyz/anchor";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { Oracle } from "../target/types/oracle";
+
+// Compute-unit budget for the price write path. No validator run has been
+// recorded for these ceilings yet: they are upper bounds picked by hand, and
+// should be lowered to the logged numbers plus a margin once `anchor test`
+// has been run. A Borsh `Account<State>` baseline can no longer be measured
+// from this tree, so what the zero-copy State buys is checked directly: a
+// write touches only the asset's entry, so its cost must not grow with the
+// size of the table. With Borsh every write decoded and re-encoded all of
+// it, hundreds of bytes per asset.
+const SET_PRICE_CU_CEILING = 20_000;
+const BATCH_10_CU_CEILING = 60_000;
+const EXTRA_ASSETS = 20;
+const CU_PER_EXTRA_ASSET_CEILING = 50;
+
+describe("oracle compute units", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.oracle as Program<Oracle>;
+  const relay = Keypair.generate();
+  const [statePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("state_v2")],
+    program.programId
+  );
+  const [programData] = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  );
+
+  async function setPriceUnits(price: number): Promise<number> {
+    const sig = await program.methods
+      .setPrice(1, 1, new BN(price), new BN(Date.now()), null)
+      .accountsPartial({ state: statePda, signer: relay.publicKey })
+      .signers([relay])
+      .rpc({ commitment: "confirmed" });
+    return unitsConsumed(sig);
+  }
+
+  async function unitsConsumed(sig: string): Promise<number> {
+    const tx = await provider.connection.getTransaction(sig, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return tx.meta.computeUnitsConsumed;
+  }
+
+  before(async () => {
+    await program.methods
+      .initialize(provider.wallet.publicKey)
+      .accountsPartial({
+        state: statePda,
+        programData,
+        upgradeAuthority: provider.wallet.publicKey,
+        payer: provider.wallet.publicKey,
+      })
+      .rpc({ commitment: "confirmed" });
+    await program.methods
+      .addUpdater(1, relay.publicKey)
+      .accountsPartial({ state: statePda, signer: provider.wallet.publicKey })
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("set_price", async () => {
+    const cu = await setPriceUnits(65_000_000000);
+    console.log(`    set_price: ${cu} CU`);
+    expect(cu).to.be.lessThan(SET_PRICE_CU_CEILING);
+  });
+
+  it("batch_set_prices (10 assets)", async () => {
+    const updates = Array.from({ length: 10 }, (_, i) => ({
+      asset: i + 1,
+      price: new BN((i + 1) * 1_000000),
+    }));
+    const sig = await program.methods
+      .batchSetPrices(1, updates, new BN(Date.now()), null)
+      .accountsPartial({ state: statePda, signer: relay.publicKey })
+      .signers([relay])
+      .rpc({ commitment: "confirmed" });
+
+    const cu = await unitsConsumed(sig);
+    console.log(`    batch_set_prices x10: ${cu} CU`);
+    expect(cu).to.be.lessThan(BATCH_10_CU_CEILING);
+  });
+
+  it("set_price cost does not grow with the asset table", async () => {
+    const before = await setPriceUnits(65_100_000000);
+    for (let id = 100; id < 100 + EXTRA_ASSETS; id++) {
+      await program.methods
+        .addAsset(id, `TEST${id}`, 8)
+        .accountsPartial({
+          state: statePda,
+          signer: provider.wallet.publicKey,
+          payer: provider.wallet.publicKey,
+        })
+        .rpc({ commitment: "confirmed" });
+    }
+    const after = await setPriceUnits(65_200_000000);
+
+    console.log(`    set_price: ${before} CU with 10 assets, ${after} CU with ${10 + EXTRA_ASSETS}`);
+    expect(after - before).to.be.lessThan(EXTRA_ASSETS * CU_PER_EXTRA_ASSET_CEILING);
+  });
+});